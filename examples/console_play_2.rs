@@ -25,7 +25,7 @@ fn get_xo_pos() -> XOPos {
 
         // parse row, col into XOPos
         Ok(XOPos::row_col(row, col)?)
-    };
+    }
 
     loop {
         match try_get_xo_pos() {
@@ -0,0 +1,142 @@
+use crate::board::{XOBoard, XOGameResult};
+use crate::xo_pos::XOPos;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// [`XOBoard`] that can be shared and played between threads without lock
+///
+/// Every operation is a single atomic operation on the 32 bit board,
+/// [`AtomicXOBoard::play`] retry with compare-and-swap until its move is applied
+/// or become illegal.
+///
+/// ```rust
+/// use bitboard_xo::{AtomicXOBoard, XOBoard, XOPos};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let shared = Arc::new(AtomicXOBoard::new(XOBoard::empty()));
+/// let handles: Vec<_> = (0..9)
+///     .map(|index| {
+///         let shared = Arc::clone(&shared);
+///         thread::spawn(move || shared.play(XOPos::index(index).unwrap()).is_ok())
+///     })
+///     .collect();
+/// let played = handles
+///     .into_iter()
+///     .filter_map(|handle| handle.join().ok())
+///     .filter(|&played| played)
+///     .count();
+///
+/// // every move either got played or was rejected after the game ended
+/// let board = shared.load();
+/// assert!(board.game_ended());
+/// assert_eq!(played as u32, 9 - board.free_bit().count_ones());
+/// ```
+///
+/// [`XOBoard`]: struct.XOBoard.html
+/// [`AtomicXOBoard::play`]: struct.AtomicXOBoard.html#method.play
+#[derive(Debug, Default)]
+pub struct AtomicXOBoard {
+    bits: AtomicU32,
+}
+
+impl AtomicXOBoard {
+    /// Construct an atomic board holding `board`
+    pub fn new(board: XOBoard) -> Self {
+        AtomicXOBoard {
+            bits: AtomicU32::new(board.bits()),
+        }
+    }
+
+    /// Retrieve the current board
+    pub fn load(&self) -> XOBoard {
+        XOBoard::new(self.bits.load(Ordering::Acquire))
+    }
+
+    /// Replace the current board with `board`
+    pub fn store(&self, board: XOBoard) {
+        self.bits.store(board.bits(), Ordering::Release)
+    }
+
+    /// Replace the current board with `board`, returning the previous board
+    pub fn swap(&self, board: XOBoard) -> XOBoard {
+        XOBoard::new(self.bits.swap(board.bits(), Ordering::AcqRel))
+    }
+
+    /// Replace the board with `new` if it's still `current`
+    ///
+    /// Return the previous board, in `Ok` if it was replaced or `Err` if not
+    pub fn compare_exchange(&self, current: XOBoard, new: XOBoard) -> Result<XOBoard, XOBoard> {
+        self.bits
+            .compare_exchange(
+                current.bits(),
+                new.bits(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .map(XOBoard::new)
+            .map_err(XOBoard::new)
+    }
+
+    /// Play the game atomically, returning the board after the move
+    ///
+    /// # Error
+    /// Return the same error as [`XOBoard::play`] on the board at the time the move
+    /// was attempted, in which case the board is left unchanged
+    ///
+    /// [`XOBoard::play`]: struct.XOBoard.html#method.play
+    pub fn play(&self, pos: XOPos) -> XOGameResult<XOBoard> {
+        let mut current = self.load();
+        loop {
+            let next = current.play(pos)?;
+            match self.bits.compare_exchange_weak(
+                current.bits(),
+                next.bits(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Ok(next),
+                Err(actual) => current = XOBoard::new(actual),
+            }
+        }
+    }
+
+    /// Consume the atomic and return the board
+    pub fn into_inner(self) -> XOBoard {
+        XOBoard::new(self.bits.into_inner())
+    }
+}
+
+impl From<XOBoard> for AtomicXOBoard {
+    fn from(board: XOBoard) -> Self {
+        AtomicXOBoard::new(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XOGameError;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn concurrent_play_on_same_cell() {
+        for _ in 0..100 {
+            let shared = Arc::new(AtomicXOBoard::default());
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let shared = Arc::clone(&shared);
+                    thread::spawn(move || shared.play(XOPos::index(4).unwrap()))
+                })
+                .collect();
+            let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+            let expected = XOBoard::empty().play(XOPos::index(4).unwrap()).unwrap();
+            assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+            assert!(results.iter().all(|result| *result == Ok(expected)
+                || *result == Err(XOGameError::AlreadyPlayedError { index: 4 })));
+            assert_eq!(Arc::try_unwrap(shared).unwrap().into_inner(), expected);
+        }
+    }
+}
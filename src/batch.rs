@@ -0,0 +1,240 @@
+use crate::board::{XOBoard, BIT_MASK, BIT_SHIFT, RULES_SHIFT, TURN_SHIFT, WIN_STATE_SHIFT};
+use crate::token::XOTokenWinState;
+use crate::xo_pos::XOPos;
+
+use std::iter::FromIterator;
+
+const WIN_STATE_MASK: u32 = 0b111 << WIN_STATE_SHIFT;
+
+/// 1 if 9 bit mask `b` contain a line, else 0 (without branch)
+#[inline(always)]
+fn line(b: u32) -> u32 {
+    let rows = b & b >> 1 & b >> 2 & 0o111;
+    let cols = b & b >> 3 & b >> 6 & 0o007;
+    ((rows | cols) != 0) as u32 | (b & 0o124 == 0o124) as u32 | (b & 0o421 == 0o421) as u32
+}
+
+/// Win state bits (before shifting) of the cells in `bits`, as evaluated by [`XOBoard::evaluate_winner`]
+///
+/// [`XOBoard::evaluate_winner`]: struct.XOBoard.html#method.evaluate_winner
+#[inline(always)]
+fn winner_bits(bits: u32) -> u32 {
+    let x_line = line(bits & BIT_MASK);
+    let o_line = line(bits >> BIT_SHIFT & BIT_MASK);
+    let full = ((bits | bits >> BIT_SHIFT) & BIT_MASK == BIT_MASK) as u32;
+    // 0b011 X, 0b101 O, 0b111 stale, misère give the line to the other player
+    let misere = bits >> RULES_SHIFT & 0b1;
+    let x_winner = 0b011 + 0b010 * misere;
+    let o_winner = 0b101 - 0b010 * misere;
+    x_line * x_winner + (1 - x_line) * (o_line * o_winner + (1 - o_line) * full * 0b111)
+}
+
+fn decode_win_state(bits: u32) -> Option<XOTokenWinState> {
+    XOBoard::new(bits << WIN_STATE_SHIFT).win_state()
+}
+
+/// Many boards stored as struct-of-arrays, one `u32` lane per board using the layout of [`XOBoard`]
+///
+/// Operations go through every lane in a loop without branch on board content,
+/// which the compiler can vectorize.
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::{XOBoard, XOBoardBatch, XOPos};
+///
+/// let mut batch = XOBoardBatch::from_boards(&[XOBoard::empty(); 3]);
+/// let played = batch.play(&[XOPos::index(0)?, XOPos::index(4)?, XOPos::index(8)?]);
+/// assert_eq!(played, 3);
+///
+/// // the second lane play on an occupied cell, which is skipped
+/// let played = batch.play(&[XOPos::index(1)?, XOPos::index(4)?, XOPos::index(0)?]);
+/// assert_eq!(played, 2);
+/// assert_eq!(batch.get(1), Some(XOBoard::empty().play(XOPos::index(4)?)?));
+/// assert_eq!(batch.legal_move_masks()[1], 0b111_101_111);
+/// assert_eq!(batch.evaluate_winners(), vec![None; 3]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`XOBoard`]: struct.XOBoard.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct XOBoardBatch {
+    lanes: Vec<u32>,
+}
+
+impl XOBoardBatch {
+    /// Construct an empty batch
+    pub fn new() -> Self {
+        XOBoardBatch::default()
+    }
+
+    /// Construct a batch holding a copy of `boards`
+    pub fn from_boards(boards: &[XOBoard]) -> Self {
+        boards.iter().copied().collect()
+    }
+
+    /// Add `board` as a new lane
+    pub fn push(&mut self, board: XOBoard) {
+        self.lanes.push(board.bits());
+    }
+
+    /// Number of lanes
+    pub fn len(&self) -> usize {
+        self.lanes.len()
+    }
+
+    /// Whether the batch has no lane
+    pub fn is_empty(&self) -> bool {
+        self.lanes.is_empty()
+    }
+
+    /// Board at lane `index`, `None` if out of bound
+    pub fn get(&self, index: usize) -> Option<XOBoard> {
+        self.lanes.get(index).map(|&bits| XOBoard::new(bits))
+    }
+
+    /// Raw bits of every lane, see [`XOBoard::bits`]
+    ///
+    /// [`XOBoard::bits`]: struct.XOBoard.html#method.bits
+    pub fn lanes(&self) -> &[u32] {
+        &self.lanes
+    }
+
+    /// Iterate through boards of every lane
+    pub fn boards(&self) -> impl Iterator<Item = XOBoard> + '_ {
+        self.lanes.iter().map(|&bits| XOBoard::new(bits))
+    }
+
+    /// Play `moves[i]` at lane `i` the same as [`XOBoard::play`], returning number of lanes played
+    ///
+    /// Lanes where the move is illegal (game ended or cell occupied) are left untouched.
+    ///
+    /// # Panics
+    /// Panic if `moves` doesn't have exactly one move per lane
+    ///
+    /// [`XOBoard::play`]: struct.XOBoard.html#method.play
+    pub fn play(&mut self, moves: &[XOPos]) -> usize {
+        assert_eq!(moves.len(), self.lanes.len(), "expected one move per lane");
+
+        let mut played = 0;
+        for (lane, pos) in self.lanes.iter_mut().zip(moves) {
+            let bits = *lane;
+            let index = pos.as_index();
+            let turn = bits >> TURN_SHIFT & 0b1;
+            let misere = bits >> RULES_SHIFT & 0b1;
+            let ended = bits >> WIN_STATE_SHIFT & 0b1;
+            let occupied = (bits >> index | bits >> (index + BIT_SHIFT)) & 0b1;
+            let legal = 1 ^ (ended | occupied);
+
+            let placed = bits | 0b1 << (index + BIT_SHIFT * turn);
+            // the game was ongoing, so only a line of the player who just played can appear
+            let mover_line = line(placed >> (BIT_SHIFT * turn) & BIT_MASK);
+            let full = ((placed | placed >> BIT_SHIFT) & BIT_MASK == BIT_MASK) as u32;
+            let winner = 0b011 + 0b010 * (turn ^ misere);
+            let win_state = mover_line * winner + (1 - mover_line) * full * 0b111;
+            let next =
+                (placed & !WIN_STATE_MASK | win_state << WIN_STATE_SHIFT) ^ 0b1 << TURN_SHIFT;
+
+            *lane = bits ^ ((bits ^ next) & 0_u32.wrapping_sub(legal));
+            played += legal as usize;
+        }
+        played
+    }
+
+    /// Evaluate win state of every lane from its cells, see [`XOBoard::evaluate_winner`]
+    ///
+    /// [`XOBoard::evaluate_winner`]: struct.XOBoard.html#method.evaluate_winner
+    pub fn evaluate_winners(&self) -> Vec<Option<XOTokenWinState>> {
+        let winners: Vec<u32> = self.lanes.iter().map(|&bits| winner_bits(bits)).collect();
+        winners.into_iter().map(decode_win_state).collect()
+    }
+
+    /// Replace win state bits of every lane with its evaluated win state,
+    /// see [`XOBoardBatch::evaluate_winners`]
+    ///
+    /// [`XOBoardBatch::evaluate_winners`]: struct.XOBoardBatch.html#method.evaluate_winners
+    pub fn update_win_states(&mut self) {
+        for lane in self.lanes.iter_mut() {
+            *lane = *lane & !WIN_STATE_MASK | winner_bits(*lane) << WIN_STATE_SHIFT;
+        }
+    }
+
+    /// Legal move mask of every lane, see [`XOBoard::legal_move_bit`]
+    ///
+    /// [`XOBoard::legal_move_bit`]: struct.XOBoard.html#method.legal_move_bit
+    pub fn legal_move_masks(&self) -> Vec<u32> {
+        self.lanes
+            .iter()
+            .map(|&bits| {
+                let ongoing = 1 ^ (bits >> WIN_STATE_SHIFT & 0b1);
+                !(bits | bits >> BIT_SHIFT) & BIT_MASK & 0_u32.wrapping_sub(ongoing)
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<XOBoard> for XOBoardBatch {
+    fn from_iter<I: IntoIterator<Item = XOBoard>>(iter: I) -> Self {
+        XOBoardBatch {
+            lanes: iter.into_iter().map(XOBoard::bits).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XORules;
+    use std::collections::HashSet;
+
+    fn reachable_boards() -> Vec<XOBoard> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty(), XOBoard::empty().swap_turn()];
+        stack.extend(
+            stack
+                .clone()
+                .iter()
+                .map(|board| board.set_rules(XORules::Misere)),
+        );
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                stack.extend(board.children());
+            }
+        }
+        seen.into_iter().collect()
+    }
+
+    #[test]
+    fn agree_with_board() -> crate::XOResult {
+        let boards = reachable_boards();
+        let mut batch = XOBoardBatch::from_boards(&boards);
+
+        let winners = batch.evaluate_winners();
+        let masks = batch.legal_move_masks();
+        for (index, board) in boards.iter().enumerate() {
+            assert_eq!(winners[index], board.evaluate_winner());
+            assert_eq!(masks[index], board.legal_move_bit());
+        }
+
+        for index in 0..9 {
+            let pos = XOPos::index(index)?;
+            let mut played = batch.clone();
+            let count = played.play(&vec![pos; boards.len()]);
+            let mut expected_count = 0;
+            for (lane, board) in boards.iter().enumerate() {
+                let expected = board.play(pos).unwrap_or(*board);
+                expected_count += (expected != *board) as usize;
+                assert_eq!(played.get(lane), Some(expected));
+            }
+            assert_eq!(count, expected_count);
+        }
+
+        batch.update_win_states();
+        assert!(batch
+            .boards()
+            .zip(boards)
+            .all(|(lane, board)| lane == board));
+        Ok(())
+    }
+}
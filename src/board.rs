@@ -1,3 +1,4 @@
+use crate::table;
 use crate::token::{XOToken, XOTokenWinState};
 use crate::xo_pos::XOPos;
 
@@ -14,6 +15,7 @@ custom_error! {
 /// [`XO::play`]: struct.XOBoard.html/#method.play
 /// [`XOBoard::play`]: struct.XOBoard.html/#method.play
 #[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub XOGameError
     /// Error of trying to play at position that isn't empty (position that already been play)
     AlreadyPlayedError{index: u32} = "Position index {index} has already been play",
@@ -23,14 +25,29 @@ pub XOGameError
 use XOGameError::*;
 pub type XOGameResult<T = ()> = Result<T, XOGameError>;
 
+/// Rules deciding who win when a player complete a line
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum XORules {
+    /// The player completing a line win
+    #[default]
+    Normal,
+    /// Misère: the player completing a line lose
+    Misere,
+}
+
 #[must_use = "XOBoard's method doesn't mutate but return a new board"]
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct XOBoard {
     bit_board: u32,
 }
 
-const BIT_MASK: u32 = 0b111_111_111;
-const BIT_SHIFT: u32 = 9;
+// bit layout of the board, see XOBoard::new
+pub(crate) const BIT_MASK: u32 = 0b111_111_111;
+pub(crate) const BIT_SHIFT: u32 = 9;
+pub(crate) const TURN_SHIFT: u32 = 2 * BIT_SHIFT;
+pub(crate) const WIN_STATE_SHIFT: u32 = TURN_SHIFT + 1;
+pub(crate) const RULES_SHIFT: u32 = WIN_STATE_SHIFT + 3;
 
 impl XOBoard {
     pub fn new(bits: u32) -> Self {
@@ -41,6 +58,33 @@ impl XOBoard {
         XOBoard::new(0)
     }
 
+    /// Construct an empty board played with `rules`, with X as starting player
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos, XORules, XOTokenWinState};
+    ///
+    /// let mut board = XOBoard::empty_with_rules(XORules::Misere);
+    /// for &index in [0, 3, 1, 4, 2].iter() {
+    ///     board = board.play(XOPos::index(index)?)?;
+    /// }
+    /// // X completed the top row, so X lose
+    /// assert_eq!(board.win_state(), Some(XOTokenWinState::O));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn empty_with_rules(rules: XORules) -> Self {
+        XOBoard::empty().set_rules(rules)
+    }
+
+    /// Retrieve the raw bits, the inverse of [`XOBoard::new`]
+    ///
+    /// [`XOBoard::new`]: struct.XOBoard.html#method.new
+    pub fn bits(self) -> u32 {
+        self.bit_board
+    }
+
     pub fn from_maybe_token_array(arr: [Option<XOToken>; 9]) -> Self {
         let mut bits = 0_u32;
         for (index, maybe_token) in arr.iter().enumerate() {
@@ -70,7 +114,7 @@ impl XOBoard {
     }
 
     pub fn turn(self) -> XOToken {
-        if ((self.bit_board >> (2 * BIT_SHIFT)) & 0b1) == 1 {
+        if ((self.bit_board >> TURN_SHIFT) & 0b1) == 1 {
             XOToken::O
         } else {
             XOToken::X
@@ -78,11 +122,11 @@ impl XOBoard {
     }
 
     pub fn swap_turn(self) -> XOBoard {
-        XOBoard::new(self.bit_board ^ (0b1 << (2 * BIT_SHIFT)))
+        XOBoard::new(self.bit_board ^ (0b1 << TURN_SHIFT))
     }
 
     pub fn win_state(self) -> Option<XOTokenWinState> {
-        match (self.bit_board >> (2 * BIT_SHIFT + 1)) & 0b111 {
+        match (self.bit_board >> WIN_STATE_SHIFT) & 0b111 {
             0b000 => None,
             0b011 => Some(XOTokenWinState::X),
             0b101 => Some(XOTokenWinState::O),
@@ -92,9 +136,36 @@ impl XOBoard {
     }
 
     pub fn game_ended(self) -> bool {
-        (self.bit_board >> (2 * BIT_SHIFT + 1)) & 0b1 == 1
+        (self.bit_board >> WIN_STATE_SHIFT) & 0b1 == 1
     }
 
+    /// Rules this board is played with
+    pub fn rules(self) -> XORules {
+        if (self.bit_board >> RULES_SHIFT) & 0b1 == 1 {
+            XORules::Misere
+        } else {
+            XORules::Normal
+        }
+    }
+
+    /// Replace the rules bit with `rules`, keeping the win state bits as is
+    pub fn set_rules(self, rules: XORules) -> XOBoard {
+        let rules_bit = match rules {
+            XORules::Normal => 0,
+            XORules::Misere => 1,
+        };
+        XOBoard::new((self.bit_board & !(0b1 << RULES_SHIFT)) | (rules_bit << RULES_SHIFT))
+    }
+
+    /// Winner when `token` complete a line under this board's rules
+    fn line_winner(self, token: XOToken) -> XOTokenWinState {
+        match self.rules() {
+            XORules::Normal => XOTokenWinState::from(token),
+            XORules::Misere => XOTokenWinState::from(token.opposite_token()),
+        }
+    }
+
+    /// Replace the win state bits with `win_state`
     pub fn set_win_state(self, win_state: Option<XOTokenWinState>) -> XOBoard {
         let win_state_bits =
             // TODO: make this on XOTokenWinState Type
@@ -105,7 +176,9 @@ impl XOBoard {
                 Some(XOTokenWinState::Stale) => 0b111,
             };
 
-        XOBoard::new(self.bit_board | (win_state_bits << (2 * BIT_SHIFT + 1)))
+        XOBoard::new(
+            (self.bit_board & !(0b111 << WIN_STATE_SHIFT)) | (win_state_bits << WIN_STATE_SHIFT),
+        )
     }
 
     fn token_mask(self, token: XOToken, pos: XOPos) -> u32 {
@@ -135,6 +208,11 @@ impl XOBoard {
         )
     }
 
+    /// Remove any token at `pos`
+    pub fn clear(self, pos: XOPos) -> XOBoard {
+        XOBoard::new(self.bit_board & !self.x_mask(pos) & !self.o_mask(pos))
+    }
+
     pub fn play(self, pos: XOPos) -> XOGameResult<XOBoard> {
         if self.game_ended() {
             return Err(GameEndedError);
@@ -146,9 +224,88 @@ impl XOBoard {
             });
         }
 
-        let board = self.set(self.turn(), pos);
-        let board = board.set_win_state(board.evaluate_winner()).swap_turn();
-        Ok(board)
+        Ok(self.play_unchecked(pos))
+    }
+
+    /// Play at `pos` without checking that the game is ongoing and `pos` is free
+    pub(crate) fn play_unchecked(self, pos: XOPos) -> XOBoard {
+        let turn = self.turn();
+        let board = self.set(turn, pos);
+
+        // the game was ongoing, so only a line of the player who just played can appear
+        let mover_bit = match turn {
+            X => board.x_bit(),
+            O => board.o_bit(),
+        };
+        let win_state = if table::has_line_lookup(mover_bit) {
+            Some(board.line_winner(turn))
+        } else if board.free_bit() == 0 {
+            Some(XOTokenWinState::Stale)
+        } else {
+            None
+        };
+        board.set_win_state(win_state).swap_turn()
+    }
+
+    /// Bit mask of empty cells, using the same cell layout as [`XOBoard::x_bit`]
+    ///
+    /// [`XOBoard::x_bit`]: struct.XOBoard.html#method.x_bit
+    pub fn free_bit(self) -> u32 {
+        !(self.x_bit() | self.o_bit()) & BIT_MASK
+    }
+
+    /// Bit mask of cells that can be played, which is [`XOBoard::free_bit`]
+    /// while the game is ongoing and `0` once [`XOBoard::game_ended`]
+    ///
+    /// [`XOBoard::free_bit`]: struct.XOBoard.html#method.free_bit
+    /// [`XOBoard::game_ended`]: struct.XOBoard.html#method.game_ended
+    pub fn legal_move_bit(self) -> u32 {
+        if self.game_ended() {
+            0
+        } else {
+            self.free_bit()
+        }
+    }
+
+    /// Iterate through every legal move in index order
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let board = XOBoard::empty().play(XOPos::index(4)?)?;
+    /// assert_eq!(board.legal_moves().len(), 8);
+    /// assert!(board.legal_moves().all(|pos| pos.as_index() != 4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn legal_moves(self) -> MoveIter {
+        MoveIter::new(self.legal_move_bit())
+    }
+
+    /// Iterate through every board reachable by one legal move,
+    /// in the same order as [`XOBoard::legal_moves`]
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos, XOToken};
+    ///
+    /// let board = XOBoard::empty();
+    /// assert_eq!(board.children().count(), 9);
+    /// assert!(board.children().all(|child| child.turn() == XOToken::O));
+    /// assert_eq!(board.children().next(), Some(board.play(XOPos::index(0)?)?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::legal_moves`]: struct.XOBoard.html#method.legal_moves
+    pub fn children(self) -> ChildrenIter {
+        ChildrenIter {
+            board: self,
+            moves: self.legal_moves(),
+        }
     }
 
     pub fn check_free_position(self, pos: XOPos) -> bool {
@@ -170,22 +327,17 @@ impl XOBoard {
         (self.o_bit() & self.x_bit()) == 0
     }
 
+    /// Evaluate win state from the cells, using a lookup table of every 9 bit pattern
+    ///
+    /// Under [`XORules::Misere`], the player completing a line is the loser
+    ///
+    /// [`XORules::Misere`]: enum.XORules.html#variant.Misere
     pub fn evaluate_winner(self) -> Option<XOTokenWinState> {
-        let win_pattern_match = |b| {
-            if b & b >> 1 & b >> 2 & 0o111_u32 != 0_u32
-                || b & b >> 3 & b >> 6 & 7_u32 != 0_u32
-                || b & 0o124 == 0o124
-                || b & 0o421 == 0o421
-            {
-                Outcome::Success
-            } else {
-                Outcome::Failure
-            }
-        };
+        let win_pattern_match = |b| Outcome::from(table::has_line_lookup(b));
 
         win_pattern_match(self.x_bit())
-            .or_none(XOTokenWinState::X)
-            .or_else(|| win_pattern_match(self.o_bit()).or_none(XOTokenWinState::O))
+            .or_none(self.line_winner(X))
+            .or_else(|| win_pattern_match(self.o_bit()).or_none(self.line_winner(O)))
             .or_else(|| {
                 Outcome::from(self.x_bit() | self.o_bit() == BIT_MASK)
                     .or_none(XOTokenWinState::Stale)
@@ -197,15 +349,30 @@ impl XOBoard {
     }
 }
 
+impl Default for XOBoard {
+    fn default() -> Self {
+        XOBoard::empty()
+    }
+}
+
 impl Display for XOBoard {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        let misere = self.rules() == XORules::Misere;
         if let Some(winner) = self.win_state() {
             match winner {
                 XOTokenWinState::Stale => writeln!(f, "Game Ended In Stale Mate")?,
+                XOTokenWinState::X if misere => {
+                    writeln!(f, "Game Ended: X's winner (O completed a line in misère)")?
+                }
+                XOTokenWinState::O if misere => {
+                    writeln!(f, "Game Ended: O's winner (X completed a line in misère)")?
+                }
                 XOTokenWinState::X | XOTokenWinState::O => {
                     writeln!(f, "Game Ended: {}'s winner", winner)?
                 }
             }
+        } else if misere {
+            writeln!(f, "{}'s Turn (misère)", self.turn())?;
         } else {
             writeln!(f, "{}'s Turn", self.turn())?;
         }
@@ -284,9 +451,9 @@ impl Display for XOBoard {
 ///
 /// # Notes
 /// * Since [`XO`] and [`XOBoard`] are copy type,
-/// this struct doesn't store mutable reference but store a copy [`XOBoard`].
-/// so modifying the original [`XOBoard`] while iterating with this struct is both possible and safe
-/// (but might be unexpected)
+///   this struct doesn't store mutable reference but store a copy [`XOBoard`].
+///   so modifying the original [`XOBoard`] while iterating with this struct is both possible and safe
+///   (but might be unexpected)
 ///
 /// [`XOBoard`]: struct.XOBoard.html
 /// [`XO`]: struct.XO.html
@@ -329,11 +496,127 @@ impl Iterator for BoardIter {
     }
 }
 
+/// Iterator through cells of a bit mask, yielding [`XOPos`] in index order
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOBoard::legal_moves`]
+/// or [`XO::legal_moves`]
+///
+/// [`XOPos`]: struct.XOPos.html
+/// [`XOBoard::legal_moves`]: struct.XOBoard.html#method.legal_moves
+/// [`XO::legal_moves`]: struct.XO.html#method.legal_moves
+#[derive(Copy, Clone, Debug)]
+pub struct MoveIter {
+    bits: u32,
+}
+
+impl MoveIter {
+    pub(crate) fn new(bits: u32) -> MoveIter {
+        MoveIter {
+            bits: bits & BIT_MASK,
+        }
+    }
+}
+
+impl Iterator for MoveIter {
+    type Item = XOPos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let index = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        Some(XOPos::new_unchecked(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for MoveIter {}
+
+/// Iterator through boards reachable from [`XOBoard`] by one legal move
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOBoard::children`]
+///
+/// [`XOBoard`]: struct.XOBoard.html
+/// [`XOBoard::children`]: struct.XOBoard.html#method.children
+#[derive(Copy, Clone, Debug)]
+pub struct ChildrenIter {
+    board: XOBoard,
+    moves: MoveIter,
+}
+
+impl Iterator for ChildrenIter {
+    type Item = XOBoard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board = self.board;
+        self.moves.next().map(|pos| board.play_unchecked(pos))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.moves.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ChildrenIter {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::XOResult;
 
+    /// Bit trick version of `evaluate_winner`, testing every line pattern on each call
+    fn evaluate_winner_bit_trick(board: XOBoard) -> Option<XOTokenWinState> {
+        let win_pattern_match = |b| {
+            if b & b >> 1 & b >> 2 & 0o111_u32 != 0_u32
+                || b & b >> 3 & b >> 6 & 7_u32 != 0_u32
+                || b & 0o124 == 0o124
+                || b & 0o421 == 0o421
+            {
+                Outcome::Success
+            } else {
+                Outcome::Failure
+            }
+        };
+
+        win_pattern_match(board.x_bit())
+            .or_none(XOTokenWinState::X)
+            .or_else(|| win_pattern_match(board.o_bit()).or_none(XOTokenWinState::O))
+            .or_else(|| {
+                Outcome::from(board.x_bit() | board.o_bit() == BIT_MASK)
+                    .or_none(XOTokenWinState::Stale)
+            })
+    }
+
+    #[test]
+    fn lookup_agree_with_bit_trick() {
+        for x_bit in 0..=BIT_MASK {
+            for o_bit in 0..=BIT_MASK {
+                let board = XOBoard::new(x_bit | o_bit << BIT_SHIFT);
+                assert_eq!(board.evaluate_winner(), evaluate_winner_bit_trick(board));
+            }
+        }
+    }
+
+    #[test]
+    fn play_agree_with_evaluate_winner() {
+        let mut seen = std::collections::HashSet::new();
+        let mut stack = vec![XOBoard::empty(), XOBoard::empty().swap_turn()];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                assert_eq!(board.win_state(), evaluate_winner_bit_trick(board));
+                stack.extend(board.children());
+            }
+        }
+    }
+
     #[test]
     fn x_win() -> XOResult {
         let mut board = XOBoard::empty();
@@ -350,6 +633,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn misere() -> XOResult {
+        let mut board = XOBoard::empty_with_rules(XORules::Misere);
+        assert_eq!(board.rules(), XORules::Misere);
+        for &index in [0, 3, 1, 4, 6, 5].iter() {
+            board = board.play(XOPos::index(index)?)?;
+        }
+
+        // O completed the middle row, so X win
+        assert_eq!(board.win_state(), Some(XOTokenWinState::X));
+        assert_eq!(board.evaluate_winner(), Some(XOTokenWinState::X));
+        assert_eq!(board.rules(), XORules::Misere);
+        assert_eq!(
+            board.to_string().lines().next(),
+            Some("Game Ended: X's winner (O completed a line in misère)")
+        );
+        assert_eq!(
+            board.set_rules(XORules::Normal).evaluate_winner(),
+            Some(XOTokenWinState::O)
+        );
+
+        // every reachable misère board agree with the normal board of the same cells
+        let mut seen = std::collections::HashSet::new();
+        let mut stack = vec![XOBoard::empty_with_rules(XORules::Misere)];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                let normal = board.set_rules(XORules::Normal);
+                let expected = match normal.evaluate_winner() {
+                    Some(XOTokenWinState::X) => Some(XOTokenWinState::O),
+                    Some(XOTokenWinState::O) => Some(XOTokenWinState::X),
+                    other => other,
+                };
+                assert_eq!(board.win_state(), expected);
+                assert_eq!(board.evaluate_winner(), expected);
+                stack.extend(board.children());
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn stale() -> XOResult {
         let mut board = XOBoard::empty();
@@ -367,4 +690,32 @@ mod tests {
         assert_eq!(board.win_state(), Some(XOTokenWinState::Stale));
         Ok(())
     }
+
+    #[test]
+    fn legal_moves() -> XOResult {
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(4)?)?;
+        assert_eq!(board.legal_move_bit(), 0b111_101_110);
+        assert!(board
+            .legal_moves()
+            .all(|pos| board.check_free_position(pos)));
+        assert!(board
+            .legal_moves()
+            .zip(board.children())
+            .all(|(pos, child)| board.play(pos) == Ok(child)));
+
+        // no move after game ended, even with free cell left
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(3)?)?
+            .play(XOPos::index(1)?)?
+            .play(XOPos::index(4)?)?
+            .play(XOPos::index(2)?)?;
+        assert!(board.game_ended());
+        assert_ne!(board.free_bit(), 0);
+        assert_eq!(board.legal_moves().count(), 0);
+        assert_eq!(board.children().count(), 0);
+        Ok(())
+    }
 }
@@ -0,0 +1,177 @@
+use crate::board::XOBoard;
+use crate::token::XOToken;
+use crate::validation::XOBoardResult;
+use crate::xo_pos::XOPos;
+use crate::XO;
+
+use std::fmt::{self, Display, Formatter};
+
+/// Editor for building positions by hand.
+///
+/// Unlike [`XOBoard::set`], every edit recompute the win state,
+/// so the edited board never disagree with its cells.
+/// Once done, [`XOEditor::into_game`] check the position and continue playing from it.
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::{XOEditor, XOPos, XOToken, XOTokenWinState};
+///
+/// let mut editor = XOEditor::new();
+/// editor
+///     .set(XOToken::X, XOPos::index(0)?)
+///     .set(XOToken::X, XOPos::index(1)?)
+///     .set(XOToken::X, XOPos::index(2)?)
+///     .set(XOToken::O, XOPos::index(3)?)
+///     .set(XOToken::O, XOPos::index(4)?);
+/// assert_eq!(editor.board().win_state(), Some(XOTokenWinState::X));
+///
+/// // taking a token back also take the win back
+/// editor.clear(XOPos::index(2)?).infer_turn();
+/// assert_eq!(editor.board().win_state(), None);
+///
+/// let mut game = editor.into_game()?;
+/// assert_eq!(game.turn(), XOToken::X);
+/// assert_eq!(game.play(XOPos::index(2)?)?, Some(XOTokenWinState::X));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`XOBoard::set`]: struct.XOBoard.html#method.set
+/// [`XOEditor::into_game`]: struct.XOEditor.html#method.into_game
+#[derive(Clone, Debug, Eq, PartialEq, Copy)]
+pub struct XOEditor {
+    board: XOBoard,
+}
+
+impl XOEditor {
+    /// Construct an editor with empty board and X as starting player.
+    pub fn new() -> Self {
+        XOEditor {
+            board: XOBoard::empty(),
+        }
+    }
+
+    /// Construct an editor starting from the cells and turn of `board`
+    ///
+    /// The win state of `board` is recomputed
+    pub fn from_board(board: XOBoard) -> Self {
+        XOEditor {
+            board: board.set_win_state(board.evaluate_winner()),
+        }
+    }
+
+    /// Place `token` at `pos`, replacing any token already there
+    pub fn set(&mut self, token: XOToken, pos: XOPos) -> &mut Self {
+        self.update(self.board.set(token, pos))
+    }
+
+    /// Remove any token at `pos`
+    pub fn clear(&mut self, pos: XOPos) -> &mut Self {
+        self.update(self.board.clear(pos))
+    }
+
+    /// Remove every token
+    pub fn clear_all(&mut self) -> &mut Self {
+        self.update(XOBoard::new(self.board.bits() & !0o777_777))
+    }
+
+    /// Set current turn's player (player who going to play)
+    pub fn set_turn(&mut self, turn: XOToken) -> &mut Self {
+        if self.board.turn() != turn {
+            self.board = self.board.swap_turn();
+        }
+        self
+    }
+
+    /// Set current turn's player from number of tokens, assuming X started:
+    /// X if both have the same number of tokens, otherwise the player with fewer tokens
+    pub fn infer_turn(&mut self) -> &mut Self {
+        let x_count = self.board.x_bit().count_ones();
+        let o_count = self.board.o_bit().count_ones();
+        self.set_turn(if x_count > o_count {
+            XOToken::O
+        } else {
+            XOToken::X
+        })
+    }
+
+    /// Retrieve the edited board
+    pub fn board(&self) -> XOBoard {
+        self.board
+    }
+
+    /// Check the edited board with [`XOBoard::validate`] and start playing from it
+    ///
+    /// # Error
+    /// Return [`XOBoardError`] if the edited position can't be reached by legal play
+    ///
+    /// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+    /// [`XOBoardError`]: enum.XOBoardError.html
+    pub fn into_game(self) -> XOBoardResult<XO> {
+        self.board.validate()?;
+        Ok(XO::from_board(self.board))
+    }
+
+    fn update(&mut self, board: XOBoard) -> &mut Self {
+        self.board = board.set_win_state(board.evaluate_winner());
+        self
+    }
+}
+
+impl Default for XOEditor {
+    fn default() -> Self {
+        XOEditor::new()
+    }
+}
+
+impl Display for XOEditor {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.board.fmt(f)
+    }
+}
+
+impl From<XO> for XOEditor {
+    fn from(game: XO) -> Self {
+        XOEditor::from_board(game.board())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::XOTokenWinState;
+    use crate::validation::XOBoardError;
+    use crate::XOResult;
+
+    #[test]
+    fn edit_finished_board() -> XOResult {
+        let mut game = XO::new();
+        for &index in [0, 3, 1, 4, 2].iter() {
+            game.play(XOPos::index(index)?)?;
+        }
+        assert_eq!(game.win_state(), Some(XOTokenWinState::X));
+
+        let mut editor = XOEditor::from(game);
+        editor.set(XOToken::O, XOPos::index(2)?);
+        assert_eq!(editor.board().win_state(), None);
+        assert_eq!(
+            editor.into_game(),
+            Err(XOBoardError::PieceCountError {
+                x_count: 2,
+                o_count: 3,
+                turn: XOToken::O
+            })
+        );
+
+        editor.infer_turn();
+        assert_eq!(editor.board().turn(), XOToken::X);
+        editor.set(XOToken::O, XOPos::index(5)?);
+        assert_eq!(editor.board().win_state(), Some(XOTokenWinState::O));
+
+        editor.clear_all();
+        assert_eq!(editor.board().win_state(), None);
+        assert_eq!(editor.into_game()?, XO::new());
+        Ok(())
+    }
+}
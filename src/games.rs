@@ -0,0 +1,365 @@
+//! Enumeration and ranking of every complete game from the empty board
+//!
+//! A complete game is a sequence of moves from [`XOBoard::empty`] played with [`XOBoard::play`]
+//! until the game ended. Games are ordered lexicographically by the index of their moves,
+//! and [`rank`] give the position of a game in that order,
+//! so a game can be stored as an integer below [`COMPLETE_GAMES`].
+//!
+//! Ranking need the number of games continuing from every position, which is tabled at
+//! compile time. [`XOBoard::play`] isn't a `const fn`, so the table is built by replaying its
+//! rules (a game end at the first line or when the board is full) directly on the cells,
+//! and the tests check that the table agree with games driven through [`XOBoard::play`].
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::games::{self, COMPLETE_GAMES};
+//! use bitboard_xo::XOPos;
+//!
+//! let game: Vec<XOPos> = ["a1", "a2", "b1", "b2", "c1"]
+//!     .iter()
+//!     .map(|coordinate| coordinate.parse())
+//!     .collect::<Result<_, _>>()?;
+//! let rank = games::rank(&game)?;
+//! assert!((rank as usize) < COMPLETE_GAMES);
+//! assert_eq!(games::unrank(rank)?, game);
+//! assert_eq!(games::all_games().nth(rank as usize), Some(game));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`XOBoard::empty`]: ../struct.XOBoard.html#method.empty
+//! [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+//! [`rank`]: fn.rank.html
+//! [`COMPLETE_GAMES`]: constant.COMPLETE_GAMES.html
+
+use crate::board::{MoveIter, XOBoard, XOGameError};
+use crate::table::{has_line, ternary_rank, TERNARY_BOARDS};
+use crate::token::XOTokenWinState;
+use crate::xo_pos::XOPos;
+
+use custom_error::custom_error;
+use std::collections::HashSet;
+
+/// Number of complete games from the empty board
+pub const COMPLETE_GAMES: usize = 255_168;
+
+custom_error! {
+/// Error use when ranking or unranking complete games
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOGameRankError
+    /// Error of an illegal move in the game
+    GameError{source: XOGameError} = "game contain an illegal move",
+    /// Error of a game that didn't end after its last move
+    IncompleteGameError{ply: usize} = "game didn't end after {ply} moves",
+    /// Error of a rank that isn't below [`COMPLETE_GAMES`]
+    ///
+    /// [`COMPLETE_GAMES`]: constant.COMPLETE_GAMES.html
+    RankOutOfRangeError{rank: u32} = "invalid rank {rank}, expecting integer in [0, 255167]",
+}
+pub type XOGameRankResult<T = ()> = Result<T, XOGameRankError>;
+
+/// Number of complete games continuing from every ternary board with X starting
+static GAME_COUNTS: [u32; TERNARY_BOARDS] = game_counts();
+
+const fn game_counts() -> [u32; TERNARY_BOARDS] {
+    let mut counts = [0_u32; TERNARY_BOARDS];
+
+    // playing a move always increase the rank, so children are counted before their parent
+    let mut rank = TERNARY_BOARDS;
+    while rank > 0 {
+        rank -= 1;
+        let mut x = 0;
+        let mut o = 0;
+        let mut rest = rank;
+        let mut index = 0;
+        while index < 9 {
+            match rest % 3 {
+                1 => x |= 0b1 << index,
+                2 => o |= 0b1 << index,
+                _ => {}
+            }
+            rest /= 3;
+            index += 1;
+        }
+
+        let free = !(x | o) & 0b111_111_111;
+        if has_line(x) || has_line(o) || free == 0 {
+            counts[rank] = 1;
+            continue;
+        }
+
+        let digit = if x.count_ones() == o.count_ones() {
+            1
+        } else {
+            2
+        };
+        let mut index = 0;
+        while index < 9 {
+            if free >> index & 0b1 == 1 {
+                counts[rank] += counts[rank + digit * 3_usize.pow(index)];
+            }
+            index += 1;
+        }
+    }
+    counts
+}
+
+fn game_count(board: XOBoard) -> u32 {
+    GAME_COUNTS[ternary_rank(board.x_bit(), board.o_bit())]
+}
+
+/// Iterate through every complete game from the empty board, in the order of [`rank`]
+///
+/// [`rank`]: fn.rank.html
+pub fn all_games() -> GameIter {
+    let board = XOBoard::empty();
+    GameIter {
+        stack: vec![(board, board.legal_moves())],
+        moves: Vec::new(),
+        last_board: board,
+    }
+}
+
+/// Rank of a complete game from the empty board, in [0, [`COMPLETE_GAMES`])
+///
+/// # Error
+/// Return [`XOGameRankError::GameError`] if a move can't be played (including moves after
+/// the game ended) or [`XOGameRankError::IncompleteGameError`] if the game didn't end
+///
+/// [`COMPLETE_GAMES`]: constant.COMPLETE_GAMES.html
+/// [`XOGameRankError::GameError`]: enum.XOGameRankError.html#variant.GameError
+/// [`XOGameRankError::IncompleteGameError`]: enum.XOGameRankError.html#variant.IncompleteGameError
+pub fn rank(moves: &[XOPos]) -> XOGameRankResult<u32> {
+    let mut board = XOBoard::empty();
+    let mut rank = 0;
+    for &pos in moves {
+        let next = board.play(pos)?;
+        rank += board
+            .legal_moves()
+            .take_while(|&sibling| sibling.as_index() < pos.as_index())
+            .map(|sibling| game_count(board.play_unchecked(sibling)))
+            .sum::<u32>();
+        board = next;
+    }
+
+    if !board.game_ended() {
+        return Err(XOGameRankError::IncompleteGameError { ply: moves.len() });
+    }
+    Ok(rank)
+}
+
+/// Moves of the game with [`rank`] `rank`
+///
+/// # Error
+/// Return [`XOGameRankError::RankOutOfRangeError`] if `rank` >= [`COMPLETE_GAMES`]
+///
+/// [`rank`]: fn.rank.html
+/// [`XOGameRankError::RankOutOfRangeError`]: enum.XOGameRankError.html#variant.RankOutOfRangeError
+/// [`COMPLETE_GAMES`]: constant.COMPLETE_GAMES.html
+pub fn unrank(rank: u32) -> XOGameRankResult<Vec<XOPos>> {
+    if rank as usize >= COMPLETE_GAMES {
+        return Err(XOGameRankError::RankOutOfRangeError { rank });
+    }
+
+    let mut board = XOBoard::empty();
+    let mut rest = rank;
+    let mut moves = Vec::new();
+    while !board.game_ended() {
+        for pos in board.legal_moves() {
+            let child = board.play(pos)?;
+            let count = game_count(child);
+            if rest < count {
+                moves.push(pos);
+                board = child;
+                break;
+            }
+            rest -= count;
+        }
+    }
+    Ok(moves)
+}
+
+/// Statistics of every complete game, computed by [`stats`]
+///
+/// [`stats`]: fn.stats.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameStats {
+    /// `by_ply[n]` is the number of games ending after `n` moves
+    pub by_ply: [u32; 10],
+    pub x_wins: u32,
+    pub o_wins: u32,
+    pub draws: u32,
+    /// Number of games distinct up to symmetry, where moves leading to
+    /// symmetric positions of the same board are counted once
+    pub symmetry_classes: u32,
+}
+
+impl GameStats {
+    /// Total number of games
+    pub fn total(&self) -> u32 {
+        self.by_ply.iter().sum()
+    }
+}
+
+/// Play through every complete game and count them by ply, outcome and symmetry class
+///
+/// ```rust
+/// use bitboard_xo::games;
+///
+/// let stats = games::stats();
+/// assert_eq!(stats.total(), 255_168);
+/// assert_eq!(stats.by_ply[5], 1440);
+/// assert_eq!((stats.x_wins, stats.o_wins, stats.draws), (131_184, 77_904, 46_080));
+/// assert_eq!(stats.symmetry_classes, 26_830);
+/// ```
+pub fn stats() -> GameStats {
+    let mut stats = GameStats {
+        by_ply: [0; 10],
+        x_wins: 0,
+        o_wins: 0,
+        draws: 0,
+        symmetry_classes: 0,
+    };
+    let mut iter = all_games();
+    while let Some(moves) = iter.next() {
+        stats.by_ply[moves.len()] += 1;
+        match iter.last_board().win_state() {
+            Some(XOTokenWinState::X) => stats.x_wins += 1,
+            Some(XOTokenWinState::O) => stats.o_wins += 1,
+            Some(XOTokenWinState::Stale) => stats.draws += 1,
+            None => unreachable!("complete game always ended"),
+        }
+    }
+
+    stats.symmetry_classes = symmetry_classes(XOBoard::empty());
+    stats
+}
+
+/// Count complete games from `board`, only following one of the moves
+/// that lead to symmetric positions
+fn symmetry_classes(board: XOBoard) -> u32 {
+    if board.game_ended() {
+        return 1;
+    }
+    let mut seen = HashSet::new();
+    board
+        .legal_moves()
+        .map(|pos| board.play(pos).expect("move is legal"))
+        .filter(|child| seen.insert(child.canonical().0))
+        .map(symmetry_classes)
+        .sum()
+}
+
+/// Iterator through complete games, constructed by [`all_games`]
+///
+/// Games are found by depth-first search playing [`XOBoard::play`].
+///
+/// [`all_games`]: fn.all_games.html
+/// [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+#[derive(Clone, Debug)]
+pub struct GameIter {
+    stack: Vec<(XOBoard, MoveIter)>,
+    moves: Vec<XOPos>,
+    last_board: XOBoard,
+}
+
+impl GameIter {
+    /// Final board of the game last returned by [`Iterator::next`]
+    pub fn last_board(&self) -> XOBoard {
+        self.last_board
+    }
+}
+
+impl Iterator for GameIter {
+    type Item = Vec<XOPos>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (board, moves) = self.stack.last_mut()?;
+            match moves.next() {
+                Some(pos) => {
+                    let child = board.play(pos).expect("move is legal");
+                    self.moves.push(pos);
+                    if child.game_ended() {
+                        let game = self.moves.clone();
+                        self.moves.pop();
+                        self.last_board = child;
+                        return Some(game);
+                    }
+                    self.stack.push((child, child.legal_moves()));
+                }
+                None => {
+                    self.stack.pop();
+                    self.moves.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_all_games() -> Result<(), XOGameRankError> {
+        let mut count = 0;
+        for (index, game) in all_games().enumerate() {
+            assert_eq!(rank(&game)?, index as u32);
+            assert_eq!(unrank(index as u32)?, game);
+            count += 1;
+        }
+        assert_eq!(count, COMPLETE_GAMES);
+        Ok(())
+    }
+
+    #[test]
+    fn counts_agree_with_play() {
+        fn count(board: XOBoard) -> u32 {
+            if board.game_ended() {
+                1
+            } else {
+                board.children().map(count).sum()
+            }
+        }
+        fn check(board: XOBoard) {
+            assert_eq!(game_count(board), count(board), "{}", board);
+            board.children().for_each(check);
+        }
+        check(XOBoard::empty());
+    }
+
+    #[test]
+    fn count_by_ply() {
+        let stats = stats();
+        assert_eq!(stats.total() as usize, COMPLETE_GAMES);
+        assert_eq!(
+            stats.by_ply,
+            [0, 0, 0, 0, 0, 1440, 5328, 47952, 72576, 127_872]
+        );
+    }
+
+    #[test]
+    fn rank_error() -> crate::XOResult {
+        let pos = |index| XOPos::index(index);
+        assert_eq!(
+            rank(&[pos(0)?, pos(0)?]),
+            Err(XOGameRankError::GameError {
+                source: XOGameError::AlreadyPlayedError { index: 0 }
+            })
+        );
+        assert_eq!(
+            rank(&[pos(0)?, pos(3)?]),
+            Err(XOGameRankError::IncompleteGameError { ply: 2 })
+        );
+        assert_eq!(
+            unrank(COMPLETE_GAMES as u32),
+            Err(XOGameRankError::RankOutOfRangeError {
+                rank: COMPLETE_GAMES as u32
+            })
+        );
+        Ok(())
+    }
+}
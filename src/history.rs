@@ -0,0 +1,214 @@
+use crate::board::{XOBoard, XOGameResult};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::xo_pos::XOPos;
+use crate::XO;
+
+use std::fmt::{self, Display, Formatter};
+
+/// Representing xo game that remember its moves, allowing takeback.
+///
+/// Behave like [`XO`] but keep every board it went through,
+/// so [`XOHistory::undo`] and [`XOHistory::redo`] restore the exact previous bits
+/// (including turn and win state).
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::{XOHistory, XOPos, XO};
+///
+/// let mut game = XOHistory::new();
+/// game.play(XOPos::index(4)?)?;
+/// game.play(XOPos::index(0)?)?;
+/// assert_eq!(game.ply(), 2);
+///
+/// assert_eq!(game.undo(), Some(XOPos::index(0)?));
+/// assert_eq!(game.last_move(), Some(XOPos::index(4)?));
+///
+/// assert_eq!(game.redo(), Some(XOPos::index(0)?));
+/// assert_eq!(game.history(), &[XOPos::index(4)?, XOPos::index(0)?]);
+///
+/// // playing after undo discard the moves that could be redone
+/// game.undo();
+/// game.play(XOPos::index(8)?)?;
+/// assert_eq!(game.redo(), None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`XO`]: struct.XO.html
+/// [`XOHistory::undo`]: struct.XOHistory.html#method.undo
+/// [`XOHistory::redo`]: struct.XOHistory.html#method.redo
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XOHistory {
+    /// `boards[i]` is the board after `i` moves, `boards[0]` is the starting board
+    boards: Vec<XOBoard>,
+    moves: Vec<XOPos>,
+    ply: usize,
+}
+
+impl XOHistory {
+    /// Construct a new game with empty board and X as starting player.
+    pub fn new() -> Self {
+        XOHistory::from_board(XOBoard::empty())
+    }
+
+    /// Construct a game starting from `board`, which can't be undone
+    pub fn from_board(board: XOBoard) -> Self {
+        XOHistory {
+            boards: vec![board],
+            moves: Vec::new(),
+            ply: 0,
+        }
+    }
+
+    /// Play the game, the same as [`XO::play`] but remembering the move.
+    ///
+    /// Moves that were undone are discarded once a move is played successfully.
+    ///
+    /// [`XO::play`]: struct.XO.html#method.play
+    pub fn play(&mut self, pos: XOPos) -> XOGameResult<Option<XOTokenWinState>> {
+        let board = self.board().play(pos)?;
+
+        self.boards.truncate(self.ply + 1);
+        self.moves.truncate(self.ply);
+        self.boards.push(board);
+        self.moves.push(pos);
+        self.ply += 1;
+
+        Ok(board.win_state())
+    }
+
+    /// Take back the last move, returning it
+    ///
+    /// Return `None` (and do nothing) if there's no move to take back
+    pub fn undo(&mut self) -> Option<XOPos> {
+        if self.ply == 0 {
+            return None;
+        }
+
+        self.ply -= 1;
+        Some(self.moves[self.ply])
+    }
+
+    /// Replay the last move taken back by [`XOHistory::undo`], returning it
+    ///
+    /// Return `None` (and do nothing) if there's no move to replay
+    ///
+    /// [`XOHistory::undo`]: struct.XOHistory.html#method.undo
+    pub fn redo(&mut self) -> Option<XOPos> {
+        let pos = *self.moves.get(self.ply)?;
+        self.ply += 1;
+        Some(pos)
+    }
+
+    /// Moves played from the starting board to the current board, in order
+    pub fn history(&self) -> &[XOPos] {
+        &self.moves[..self.ply]
+    }
+
+    /// Move that lead to the current board, `None` if at the starting board
+    pub fn last_move(&self) -> Option<XOPos> {
+        self.history().last().copied()
+    }
+
+    /// Number of moves played from the starting board to the current board
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Board the game started from
+    pub fn start_board(&self) -> XOBoard {
+        self.boards[0]
+    }
+
+    /// Retrieve the current board
+    pub fn board(&self) -> XOBoard {
+        self.boards[self.ply]
+    }
+
+    /// Retrieve the current board as [`XO`]
+    ///
+    /// [`XO`]: struct.XO.html
+    pub fn game(&self) -> XO {
+        XO::from_board(self.board())
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(&self) -> XOToken {
+        self.board().turn()
+    }
+
+    /// Get information about who (if any) won the game, see [`XO::win_state`]
+    ///
+    /// [`XO::win_state`]: struct.XO.html#method.win_state
+    pub fn win_state(&self) -> Option<XOTokenWinState> {
+        self.board().win_state()
+    }
+}
+
+impl Default for XOHistory {
+    fn default() -> Self {
+        XOHistory::new()
+    }
+}
+
+impl Display for XOHistory {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.board().fmt(f)
+    }
+}
+
+impl From<XO> for XOHistory {
+    fn from(game: XO) -> Self {
+        XOHistory::from_board(game.board())
+    }
+}
+
+impl From<XOBoard> for XOHistory {
+    fn from(board: XOBoard) -> Self {
+        XOHistory::from_board(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn undo_restore_exact_bits() -> XOResult {
+        let mut game = XOHistory::new();
+        let mut boards = vec![game.board()];
+        for &index in [0, 3, 1, 4, 2].iter() {
+            game.play(XOPos::index(index)?)?;
+            boards.push(game.board());
+        }
+        assert_eq!(game.win_state(), Some(XOTokenWinState::X));
+
+        while let Some(pos) = game.undo() {
+            boards.pop();
+            assert_eq!(game.board(), *boards.last().unwrap());
+            assert!(game.board().check_free_position(pos));
+        }
+        assert_eq!(game.ply(), 0);
+        assert_eq!(game.board(), XOBoard::empty());
+        assert_eq!(game.last_move(), None);
+
+        while game.redo().is_some() {}
+        assert_eq!(game.ply(), 5);
+        assert_eq!(game.win_state(), Some(XOTokenWinState::X));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_play_keep_redo() -> XOResult {
+        let mut game = XOHistory::new();
+        game.play(XOPos::index(0)?)?;
+        game.play(XOPos::index(1)?)?;
+        game.undo();
+
+        assert!(game.play(XOPos::index(0)?).is_err());
+        assert_eq!(game.redo(), Some(XOPos::index(1)?));
+        Ok(())
+    }
+}
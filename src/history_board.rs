@@ -0,0 +1,299 @@
+use crate::board::{XOBoard, XOGameResult, RULES_SHIFT};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::xo_pos::XOPos;
+
+use std::fmt::{self, Display, Formatter};
+
+// layout of the 64 bit:
+// bit 0-22: current board (see XOBoard::new), bit 23-26: ply,
+// bit 27-62: 4 bit index of every move played, first move at the lowest bits
+const PLY_SHIFT: u32 = RULES_SHIFT + 1;
+const BOARD_MASK: u64 = (0b1 << PLY_SHIFT) - 1;
+const PLY_MASK: u64 = 0b1111;
+const MOVE_SHIFT: u32 = PLY_SHIFT + 4;
+const MOVE_MASK: u64 = 0b1111;
+
+/// [`XOBoard`] that also remember its moves, packed in 64 bit
+///
+/// Unlike [`XOHistory`] it's `Copy` and never allocate, at the cost of only undoing
+/// the moves played (there's no redo).
+/// The current board is kept as is, so [`XOHistoryBoard::board`] is free
+/// and [`XOHistoryBoard::undo`] only take back a single cell.
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::{XOBoard, XOHistoryBoard, XOPos};
+///
+/// let game = XOHistoryBoard::new()
+///     .play(XOPos::index(4)?)?
+///     .play(XOPos::index(0)?)?;
+/// assert_eq!(std::mem::size_of_val(&game), 8);
+/// assert_eq!(game.ply(), 2);
+/// assert_eq!(game.last_move(), Some(XOPos::index(0)?));
+///
+/// let previous = game.undo().unwrap();
+/// assert_eq!(previous.board(), XOBoard::empty().play(XOPos::index(4)?)?);
+///
+/// let moves: Vec<_> = game.moves().collect();
+/// assert_eq!(moves, [XOPos::index(4)?, XOPos::index(0)?]);
+/// assert_eq!(game.replay().last(), Some(game.board()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`XOBoard`]: struct.XOBoard.html
+/// [`XOHistory`]: struct.XOHistory.html
+/// [`XOHistoryBoard::board`]: struct.XOHistoryBoard.html#method.board
+/// [`XOHistoryBoard::undo`]: struct.XOHistoryBoard.html#method.undo
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct XOHistoryBoard {
+    bits: u64,
+}
+
+impl XOHistoryBoard {
+    /// Construct a new game with empty board and X as starting player.
+    pub fn new() -> Self {
+        XOHistoryBoard::from_board(XOBoard::empty())
+    }
+
+    /// Construct a game starting from `board`, which can't be undone
+    ///
+    /// `board` should use bits as in [`XOBoard::new`], any other bit is dropped
+    ///
+    /// [`XOBoard::new`]: struct.XOBoard.html#method.new
+    pub fn from_board(board: XOBoard) -> Self {
+        XOHistoryBoard {
+            bits: u64::from(board.bits()) & BOARD_MASK,
+        }
+    }
+
+    /// Retrieve the raw bits
+    pub fn bits(self) -> u64 {
+        self.bits
+    }
+
+    /// Retrieve the current board
+    pub fn board(self) -> XOBoard {
+        XOBoard::new((self.bits & BOARD_MASK) as u32)
+    }
+
+    /// Number of moves played from the starting board
+    pub fn ply(self) -> u32 {
+        (self.bits >> PLY_SHIFT & PLY_MASK) as u32
+    }
+
+    /// Play the game, the same as [`XOBoard::play`] but remembering the move
+    ///
+    /// [`XOBoard::play`]: struct.XOBoard.html#method.play
+    pub fn play(self, pos: XOPos) -> XOGameResult<XOHistoryBoard> {
+        let board = self.board().play(pos)?;
+        // every move fill a cell, so there's at most 9 moves
+        let ply = self.ply();
+        let moves = self.bits >> MOVE_SHIFT | u64::from(pos.as_index()) << (4 * ply);
+        Ok(XOHistoryBoard {
+            bits: u64::from(board.bits()) | u64::from(ply + 1) << PLY_SHIFT | moves << MOVE_SHIFT,
+        })
+    }
+
+    /// Take back the last move, `None` if at the starting board
+    ///
+    /// The previous board always had the game ongoing (or the move couldn't be played),
+    /// so its exact bits are restored without being stored.
+    pub fn undo(self) -> Option<XOHistoryBoard> {
+        let pos = self.last_move()?;
+        let ply = self.ply() - 1;
+        let board = self.board().clear(pos).set_win_state(None).swap_turn();
+        let moves = self.bits >> MOVE_SHIFT & !(MOVE_MASK << (4 * ply));
+        Some(XOHistoryBoard {
+            bits: u64::from(board.bits()) | u64::from(ply) << PLY_SHIFT | moves << MOVE_SHIFT,
+        })
+    }
+
+    /// Move that lead to the current board, `None` if at the starting board
+    pub fn last_move(self) -> Option<XOPos> {
+        self.ply().checked_sub(1).and_then(|ply| self.move_at(ply))
+    }
+
+    /// Move played at `ply` (counting from 0), `None` if it hasn't been played
+    pub fn move_at(self, ply: u32) -> Option<XOPos> {
+        if ply >= self.ply() {
+            return None;
+        }
+        let index = self.bits >> (MOVE_SHIFT + 4 * ply) & MOVE_MASK;
+        Some(XOPos::new_unchecked(index as u32))
+    }
+
+    /// Iterate through moves played from the starting board, in order
+    pub fn moves(self) -> HistoryMoveIter {
+        HistoryMoveIter {
+            history: self,
+            ply: 0,
+        }
+    }
+
+    /// Board the game started from
+    pub fn start_board(self) -> XOBoard {
+        let mut history = self;
+        while let Some(previous) = history.undo() {
+            history = previous;
+        }
+        history.board()
+    }
+
+    /// Iterate through every board from the starting board to the current board,
+    /// by replaying the moves with [`XOBoard::play`]
+    ///
+    /// [`XOBoard::play`]: struct.XOBoard.html#method.play
+    pub fn replay(self) -> ReplayIter {
+        ReplayIter {
+            board: Some(self.start_board()),
+            moves: self.moves(),
+        }
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(self) -> XOToken {
+        self.board().turn()
+    }
+
+    /// Get information about who (if any) won the game, see [`XOBoard::win_state`]
+    ///
+    /// [`XOBoard::win_state`]: struct.XOBoard.html#method.win_state
+    pub fn win_state(self) -> Option<XOTokenWinState> {
+        self.board().win_state()
+    }
+}
+
+impl Default for XOHistoryBoard {
+    fn default() -> Self {
+        XOHistoryBoard::new()
+    }
+}
+
+impl Display for XOHistoryBoard {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.board().fmt(f)
+    }
+}
+
+impl From<XOBoard> for XOHistoryBoard {
+    fn from(board: XOBoard) -> Self {
+        XOHistoryBoard::from_board(board)
+    }
+}
+
+impl From<XOHistoryBoard> for XOBoard {
+    fn from(history: XOHistoryBoard) -> Self {
+        history.board()
+    }
+}
+
+/// Iterator through moves of [`XOHistoryBoard`]
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOHistoryBoard::moves`]
+///
+/// [`XOHistoryBoard`]: struct.XOHistoryBoard.html
+/// [`XOHistoryBoard::moves`]: struct.XOHistoryBoard.html#method.moves
+#[derive(Copy, Clone, Debug)]
+pub struct HistoryMoveIter {
+    history: XOHistoryBoard,
+    ply: u32,
+}
+
+impl Iterator for HistoryMoveIter {
+    type Item = XOPos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.history.move_at(self.ply)?;
+        self.ply += 1;
+        Some(pos)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.history.ply() - self.ply) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for HistoryMoveIter {}
+
+/// Iterator through boards of [`XOHistoryBoard`], starting with the starting board
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOHistoryBoard::replay`]
+///
+/// [`XOHistoryBoard`]: struct.XOHistoryBoard.html
+/// [`XOHistoryBoard::replay`]: struct.XOHistoryBoard.html#method.replay
+#[derive(Copy, Clone, Debug)]
+pub struct ReplayIter {
+    board: Option<XOBoard>,
+    moves: HistoryMoveIter,
+}
+
+impl Iterator for ReplayIter {
+    type Item = XOBoard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board = self.board?;
+        self.board = self
+            .moves
+            .next()
+            .map(|pos| board.play(pos).expect("recorded move is legal"));
+        Some(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XORules;
+    use crate::XOResult;
+
+    #[test]
+    fn undo_restore_exact_bits() -> XOResult {
+        for &(rules, winner) in [
+            (XORules::Normal, XOTokenWinState::X),
+            (XORules::Misere, XOTokenWinState::O),
+        ]
+        .iter()
+        {
+            let start = XOHistoryBoard::from_board(XOBoard::empty_with_rules(rules));
+            let mut game = start;
+            let mut games = vec![game];
+            for &index in [0, 3, 1, 4, 2].iter() {
+                game = game.play(XOPos::index(index)?)?;
+                games.push(game);
+            }
+            assert_eq!(game.win_state(), Some(winner));
+            assert_eq!(game.board().rules(), rules);
+            assert_eq!(
+                game.replay().collect::<Vec<_>>(),
+                games.iter().map(|game| game.board()).collect::<Vec<_>>()
+            );
+
+            while let Some(previous) = game.undo() {
+                games.pop();
+                assert_eq!(previous, *games.last().unwrap());
+                game = previous;
+            }
+            assert_eq!(game, start);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn full_board() -> XOResult {
+        let mut game = XOHistoryBoard::new();
+        for &index in [4, 0, 2, 6, 3, 5, 1, 7, 8].iter() {
+            game = game.play(XOPos::index(index)?)?;
+        }
+        assert_eq!(game.ply(), 9);
+        assert_eq!(game.win_state(), Some(XOTokenWinState::Stale));
+        assert_eq!(game.last_move(), Some(XOPos::index(8)?));
+        assert_eq!(game.moves().len(), 9);
+        assert_eq!(game.start_board(), XOBoard::empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use crate::board::XOBoard;
+use crate::symmetry::XOTransform;
+use crate::table::{ternary_rank, REACHABLE_POSITIONS, SOLUTIONS, TERNARY_BOARDS, TERNARY_WEIGHT};
+use crate::validation::{XOBoardError, XOBoardResult};
+
+/// Number of positions reachable from [`XOBoard::empty`] that are distinct up to symmetry
+///
+/// [`XOBoard::empty`]: struct.XOBoard.html#method.empty
+pub const SYMMETRY_CLASSES: usize = 765;
+
+const NO_INDEX: u16 = u16::MAX;
+
+struct DenseIndex {
+    /// position index of every ternary rank, [`NO_INDEX`] if unreachable
+    rank_to_position: [u16; TERNARY_BOARDS],
+    position_to_rank: [u16; REACHABLE_POSITIONS],
+    position_to_class: [u16; REACHABLE_POSITIONS],
+    /// position index of the canonical member of each class
+    class_to_position: [u16; SYMMETRY_CLASSES],
+}
+
+static DENSE_INDEX: DenseIndex = dense_index();
+
+const fn dense_index() -> DenseIndex {
+    let mut dense = DenseIndex {
+        rank_to_position: [NO_INDEX; TERNARY_BOARDS],
+        position_to_rank: [0; REACHABLE_POSITIONS],
+        position_to_class: [0; REACHABLE_POSITIONS],
+        class_to_position: [0; SYMMETRY_CLASSES],
+    };
+
+    // positions are numbered in increasing rank
+    let mut position = 0;
+    let mut rank = 0;
+    while rank < TERNARY_BOARDS {
+        if SOLUTIONS[rank] != 0 {
+            dense.rank_to_position[rank] = position as u16;
+            dense.position_to_rank[position] = rank as u16;
+            position += 1;
+        }
+        rank += 1;
+    }
+
+    // the canonical member of a class is the one with the smallest bits as in XOBoard::canonical
+    let mut canonical_position = [0_u16; REACHABLE_POSITIONS];
+    let mut position = 0;
+    while position < REACHABLE_POSITIONS {
+        let mut rest = dense.position_to_rank[position] as u32;
+        let mut cell_bits = 0;
+        let mut index = 0;
+        while index < 9 {
+            match rest % 3 {
+                1 => cell_bits |= 0b1 << index,
+                2 => cell_bits |= 0b1 << (index + 9),
+                _ => {}
+            }
+            rest /= 3;
+            index += 1;
+        }
+
+        let mut canonical_bits = cell_bits;
+        let mut transform = 0;
+        while transform < XOTransform::ALL.len() {
+            let bits = XOTransform::ALL[transform].apply(cell_bits);
+            if bits < canonical_bits {
+                canonical_bits = bits;
+            }
+            transform += 1;
+        }
+        let canonical_rank = TERNARY_WEIGHT[(canonical_bits & 0o777) as usize] as usize
+            + 2 * TERNARY_WEIGHT[(canonical_bits >> 9) as usize] as usize;
+        canonical_position[position] = dense.rank_to_position[canonical_rank];
+        position += 1;
+    }
+
+    // classes are numbered in increasing position index of their canonical member
+    let mut class = 0;
+    let mut position = 0;
+    while position < REACHABLE_POSITIONS {
+        if canonical_position[position] as usize == position {
+            dense.class_to_position[class] = position as u16;
+            dense.position_to_class[position] = class as u16;
+            class += 1;
+        }
+        position += 1;
+    }
+    let mut position = 0;
+    while position < REACHABLE_POSITIONS {
+        let canonical = canonical_position[position] as usize;
+        dense.position_to_class[position] = dense.position_to_class[canonical];
+        position += 1;
+    }
+
+    dense
+}
+
+impl XOBoard {
+    /// Index of this board among every position reachable from [`XOBoard::empty`],
+    /// in [0, [`REACHABLE_POSITIONS`])
+    ///
+    /// The index is a bijection usable as an array index, inverted by [`XOBoard::from_position_index`].
+    ///
+    /// Boards played with [`XORules::Misere`] reach the same cells (a game end at the first line
+    /// under both rules), so they get the index of their cells, checked against their own rules.
+    /// The rules aren't part of the index: [`XOBoard::from_position_index`] give back
+    /// the board played with [`XORules::Normal`].
+    ///
+    /// # Error
+    /// Return error of [`XOBoard::validate`] if this board is invalid,
+    /// or [`XOBoardError::UnreachableError`] if it can't be reached from [`XOBoard::empty`]
+    /// (such as valid boards where O started) or from [`XOBoard::empty_with_rules`]
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos, REACHABLE_POSITIONS};
+    ///
+    /// let mut visits = vec![0; REACHABLE_POSITIONS];
+    /// let board = XOBoard::empty().play(XOPos::index(4)?)?;
+    /// visits[board.position_index()?] += 1;
+    ///
+    /// assert_eq!(XOBoard::empty().position_index()?, 0);
+    /// assert_eq!(XOBoard::from_position_index(board.position_index()?), Some(board));
+    /// assert!(XOBoard::empty().swap_turn().position_index().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::empty`]: struct.XOBoard.html#method.empty
+    /// [`REACHABLE_POSITIONS`]: constant.REACHABLE_POSITIONS.html
+    /// [`XOBoard::from_position_index`]: struct.XOBoard.html#method.from_position_index
+    /// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+    /// [`XOBoardError::UnreachableError`]: enum.XOBoardError.html#variant.UnreachableError
+    /// [`XOBoard::empty_with_rules`]: struct.XOBoard.html#method.empty_with_rules
+    /// [`XORules::Misere`]: enum.XORules.html#variant.Misere
+    /// [`XORules::Normal`]: enum.XORules.html#variant.Normal
+    pub fn position_index(self) -> XOBoardResult<usize> {
+        self.validate()?;
+        let position = DENSE_INDEX.rank_to_position[ternary_rank(self.x_bit(), self.o_bit())];
+        if position == NO_INDEX {
+            return Err(XOBoardError::UnreachableError);
+        }
+        let reached = XOBoard::position_at(position as usize).set_rules(self.rules());
+        if reached.set_win_state(reached.evaluate_winner()) != self {
+            return Err(XOBoardError::UnreachableError);
+        }
+        Ok(position as usize)
+    }
+
+    /// Construct the board of [`XOBoard::position_index`],
+    /// return `None` if `index` >= [`REACHABLE_POSITIONS`]
+    ///
+    /// [`XOBoard::position_index`]: struct.XOBoard.html#method.position_index
+    /// [`REACHABLE_POSITIONS`]: constant.REACHABLE_POSITIONS.html
+    pub fn from_position_index(index: usize) -> Option<XOBoard> {
+        if index < REACHABLE_POSITIONS {
+            Some(XOBoard::position_at(index))
+        } else {
+            None
+        }
+    }
+
+    /// Index of this board's symmetry class among every position reachable from
+    /// [`XOBoard::empty`], in [0, [`SYMMETRY_CLASSES`])
+    ///
+    /// Boards have the same class index exactly when they are symmetric variants of each other.
+    ///
+    /// # Error
+    /// Same as [`XOBoard::position_index`]
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let corner = XOBoard::empty().play(XOPos::index(0)?)?;
+    /// let class = corner.symmetry_class_index()?;
+    /// assert_eq!(corner.rotate().symmetry_class_index()?, class);
+    /// assert_eq!(XOBoard::from_symmetry_class_index(class), Some(corner.canonical().0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::empty`]: struct.XOBoard.html#method.empty
+    /// [`SYMMETRY_CLASSES`]: constant.SYMMETRY_CLASSES.html
+    /// [`XOBoard::position_index`]: struct.XOBoard.html#method.position_index
+    pub fn symmetry_class_index(self) -> XOBoardResult<usize> {
+        let position = self.position_index()?;
+        Ok(DENSE_INDEX.position_to_class[position] as usize)
+    }
+
+    /// Construct the canonical board (see [`XOBoard::canonical`]) of [`XOBoard::symmetry_class_index`],
+    /// return `None` if `index` >= [`SYMMETRY_CLASSES`]
+    ///
+    /// [`XOBoard::canonical`]: struct.XOBoard.html#method.canonical
+    /// [`XOBoard::symmetry_class_index`]: struct.XOBoard.html#method.symmetry_class_index
+    /// [`SYMMETRY_CLASSES`]: constant.SYMMETRY_CLASSES.html
+    pub fn from_symmetry_class_index(index: usize) -> Option<XOBoard> {
+        DENSE_INDEX
+            .class_to_position
+            .get(index)
+            .map(|&position| XOBoard::position_at(position as usize))
+    }
+
+    fn position_at(position: usize) -> XOBoard {
+        XOBoard::unrank(DENSE_INDEX.position_to_rank[position]).expect("reachable rank is in range")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XORules;
+    use std::collections::HashSet;
+
+    #[test]
+    fn bijection() {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty()];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                let index = board.position_index().unwrap();
+                assert_eq!(XOBoard::from_position_index(index), Some(board));
+
+                let class = board.symmetry_class_index().unwrap();
+                assert_eq!(
+                    XOBoard::from_symmetry_class_index(class),
+                    Some(board.canonical().0)
+                );
+                stack.extend(board.children());
+            }
+        }
+        assert_eq!(seen.len(), REACHABLE_POSITIONS);
+
+        let classes: HashSet<_> = seen.iter().map(|board| board.canonical().0).collect();
+        assert_eq!(classes.len(), SYMMETRY_CLASSES);
+        assert_eq!(XOBoard::from_position_index(REACHABLE_POSITIONS), None);
+        assert_eq!(XOBoard::from_symmetry_class_index(SYMMETRY_CLASSES), None);
+    }
+
+    #[test]
+    fn misere_share_cells_index() {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty_with_rules(XORules::Misere)];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                let index = board.position_index().unwrap();
+                let normal = XOBoard::from_position_index(index).unwrap();
+                assert_eq!(normal.x_bit(), board.x_bit());
+                assert_eq!(normal.o_bit(), board.o_bit());
+                assert_eq!(normal.turn(), board.turn());
+                stack.extend(board.children());
+            }
+        }
+        assert_eq!(seen.len(), REACHABLE_POSITIONS);
+        assert_eq!(
+            XOBoard::empty_with_rules(XORules::Misere).position_index(),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn reject_unreachable() {
+        // valid but O started
+        let board = XOBoard::empty().swap_turn();
+        assert_eq!(board.validate(), Ok(()));
+        assert_eq!(board.position_index(), Err(XOBoardError::UnreachableError));
+        let board = XOBoard::empty_with_rules(XORules::Misere).swap_turn();
+        assert_eq!(board.position_index(), Err(XOBoardError::UnreachableError));
+        assert_eq!(
+            XOBoard::new(0b1 | 0b1 << 9).symmetry_class_index(),
+            Err(XOBoardError::OverlapError { index: 0 })
+        );
+    }
+}
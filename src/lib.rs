@@ -73,12 +73,17 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Features
+//! * `serde`: implement `Serialize` and `Deserialize` for public types,
+//!   boards are validated when deserialized
+//!
 //! [bitboard]: https://en.wikipedia.org/wiki/Bitboard
 
 custom_error! {
-/// Super Error combining all error in this crate ([`XOPosError`] and [`XOGameError`])
+/// Super Error combining all error in this crate ([`XOPosError`], [`XOGameError`], ...)
 ///
-/// both [`XOPosError`] and [`XOGameError`] can convert to [`XOError`] with ? operator
+/// every error in this crate can convert to [`XOError`] with ? operator
 ///
 /// ```rust
 /// use bitboard_xo::XOError;
@@ -103,21 +108,100 @@ custom_error! {
 /// [`XOPosError`]: xo_pos/enum.XOPosError.html
 /// [`XOGameError`]: enum.XOGameError.html
 /// [`XOError`]: enum.XOError.html
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub XOError
     PositionError{source: xo_pos::XOPosError} = "Invalid XO Position",
-    GameError{source: board::XOGameError} = "Error occurred when trying to play (make a move)"
+    CoordinateError{source: xo_pos::XOCoordinateError} = "Invalid XO coordinate",
+    GameError{source: board::XOGameError} = "Error occurred when trying to play (make a move)",
+    SolveError{source: solver::XOSolveError} = "Error occurred when trying to solve a board",
+    BoardError{source: validation::XOBoardError} = "Invalid XO board",
+    NotationError{source: notation::XONotationError} = "Invalid XO notation",
+    RecordError{source: record::XORecordError} = "Invalid XO game record",
+    PackError{source: packed::XOPackError} = "Invalid packed XO board or rank",
+    GameRankError{source: games::XOGameRankError} = "Invalid complete game or game rank",
+    OccupiedError{source: typestate::XOOccupiedError} = "Error occurred when trying to play (make a move)",
+    MNKPositionError{source: mnk::MNKPosError} = "Invalid m,n,k-game position",
+    QubicPositionError{source: qubic::QubicPosError} = "Invalid Qubic position",
+    UltimateError{source: ultimate::XOUltimateError} = "Error occurred when trying to play ultimate xo",
+    NotaktoError{source: notakto::NotaktoError} = "Error occurred when trying to play notakto"
 }
 pub type XOResult<T = ()> = Result<T, XOError>;
 
 // Re-export
 mod token;
-pub use token::{XOToken, XOTokenWinState};
+pub use token::{Player, XOToken, XOTokenWinState};
 
 mod board;
-pub use board::{BoardIter, XOBoard, XOGameError};
+pub use board::{BoardIter, ChildrenIter, MoveIter, XOBoard, XOGameError, XORules};
+
+mod solver;
+pub use solver::{XOSolution, XOSolveError, XOValue};
+
+mod symmetry;
+pub use symmetry::{SymmetryIter, XOTransform};
+
+mod table;
+pub use table::REACHABLE_POSITIONS;
+
+mod index;
+pub use index::SYMMETRY_CLASSES;
+
+mod packed;
+pub use packed::{PackedXOBoard, XOPackError};
+
+mod history;
+pub use history::XOHistory;
+
+mod history_board;
+pub use history_board::{HistoryMoveIter, ReplayIter, XOHistoryBoard};
+
+mod line;
+pub use line::{Line, LineIter, XOGameOutcome};
+
+mod atomic;
+pub use atomic::AtomicXOBoard;
+
+mod batch;
+pub use batch::XOBoardBatch;
+
+mod validation;
+pub use validation::XOBoardError;
+
+mod editor;
+pub use editor::XOEditor;
+
+pub mod notation;
+pub use notation::{XONotation, XONotationError};
+
+pub mod record;
+pub use record::{XORecord, XORecordError};
+
+pub mod games;
+pub use games::{GameIter, XOGameRankError};
+
+pub mod typestate;
+pub use typestate::XOOccupiedError;
+
+pub mod mnk;
+pub use mnk::{MNKBoard, MNKPos, MNKPosError};
+
+pub mod qubic;
+pub use qubic::{QubicBoard, QubicPos, QubicPosError};
+
+pub mod ultimate;
+pub use ultimate::{UltimatePos, UltimateXO, XOUltimateError};
+
+pub mod notakto;
+pub use notakto::{Notakto, NotaktoError, NotaktoPos, NotaktoValue};
+
+pub mod wild;
+pub use wild::{WildBoard, WildMove};
+
+#[cfg(feature = "serde")]
+mod serde_impl;
 
 pub mod xo_pos;
-pub use xo_pos::{XOPos, XOPosError};
+pub use xo_pos::{XOCoordinateError, XOPos, XOPosError};
 //
 
 use crate::board::XOGameResult;
@@ -153,6 +237,28 @@ impl XO {
         }
     }
 
+    /// Construct a new game with empty board played with `rules`, and X as starting player.
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XO, XOPos, XORules, XOTokenWinState};
+    ///
+    /// let mut game = XO::with_rules(XORules::Misere);
+    /// for &index in [0, 3, 1, 4].iter() {
+    ///     assert_eq!(game.play(XOPos::index(index)?)?, None);
+    /// }
+    /// // X completed the top row and lose
+    /// assert_eq!(game.play(XOPos::index(2)?)?, Some(XOTokenWinState::O));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_rules(rules: XORules) -> Self {
+        XO {
+            board: XOBoard::empty_with_rules(rules),
+        }
+    }
+
     /// Construct a game from [`XOBoard`].
     ///
     /// this function only wrap [`XOBoard`] without modifying anything in it
@@ -167,7 +273,7 @@ impl XO {
     /// Assuming no error occured:
     ///
     /// * If after playing at `pos`, the game ended ->
-    /// it return `Option::Some` containing the type of game ending as [`XOTokenWinState`]
+    ///   it return `Option::Some` containing the type of game ending as [`XOTokenWinState`]
     ///
     /// * If after playing at `pos`, the game doesn't end -> it return `Option::None`
     ///
@@ -238,6 +344,32 @@ impl XO {
         Ok(self.board.win_state())
     }
 
+    /// Play the game like [`XO::play`], but report the lines that decided the game
+    /// alongside the type of game ending
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{Line, XO, XOPos, XOTokenWinState};
+    ///
+    /// let mut game = XO::new();
+    /// for &index in [0, 3, 1, 4].iter() {
+    ///     assert_eq!(game.play_detailed(XOPos::index(index)?)?, None);
+    /// }
+    ///
+    /// let outcome = game.play_detailed(XOPos::index(2)?)?.unwrap();
+    /// assert_eq!(outcome.win_state(), XOTokenWinState::X);
+    /// assert_eq!(outcome.lines().collect::<Vec<_>>(), vec![Line::TopRow]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XO::play`]: struct.XO.html#method.play
+    pub fn play_detailed(&mut self, pos: XOPos) -> XOGameResult<Option<XOGameOutcome>> {
+        let win_state = self.play(pos)?;
+        Ok(win_state.map(|win_state| XOGameOutcome::new(self.board, win_state)))
+    }
+
     /// Swap current turn's play (player who going to play) (X -> O, O -> X)
     ///
     /// ```rust
@@ -273,8 +405,6 @@ impl XO {
     }
 
     /// Get information about who (if any) won the game
-    ///
-
     ///
     /// # Return
     /// * None if game didn't end yet
@@ -284,13 +414,13 @@ impl XO {
     ///
     /// # Notes
     /// * This function doesn't directly calculate the winner.
-    /// the calculation are already done when calling [`XO::play`] or [`XOBoard::play`],
-    /// this function only retrieve the calculated information
-    /// so it can be called repeatably without much performance penalty.
+    ///   the calculation are already done when calling [`XO::play`] or [`XOBoard::play`],
+    ///   this function only retrieve the calculated information
+    ///   so it can be called repeatably without much performance penalty.
     ///
     /// * This function output are the same as in `Ok` variant of [`XO::play`]'s output.
-    /// If this function output `None`
-    /// then [`XO::play`] will output error variant of [`XOGameError::AlreadyPlayedError`]
+    ///   If this function output `None`
+    ///   then [`XO::play`] will output error variant of [`XOGameError::AlreadyPlayedError`]
     ///
     /// [`XO::play`]: struct.XO.html#method.play
     /// [`XOBoard::play`]: struct.XOBoard.html#method.play
@@ -303,7 +433,14 @@ impl XO {
         self.board.win_state()
     }
 
-    /// Reset the board to empty default board
+    /// Rules this game is played with (see [`XORules`])
+    ///
+    /// [`XORules`]: enum.XORules.html
+    pub fn rules(self) -> XORules {
+        self.board.rules()
+    }
+
+    /// Reset the board to empty default board, keeping the rules
     ///
     /// ```rust
     /// # use bitboard_xo::XOResult;
@@ -323,7 +460,7 @@ impl XO {
     /// # }
     /// ```
     pub fn reset(&mut self) {
-        self.board = XOBoard::empty();
+        self.board = XOBoard::empty_with_rules(self.board.rules());
     }
 
     /// Return immutable iterator through the board
@@ -331,6 +468,13 @@ impl XO {
         self.board.iter()
     }
 
+    /// Return iterator through every legal move (see [`XOBoard::legal_moves`])
+    ///
+    /// [`XOBoard::legal_moves`]: struct.XOBoard.html#method.legal_moves
+    pub fn legal_moves(self) -> MoveIter {
+        self.board.legal_moves()
+    }
+
     /// Retrieve the wrapped [`XOBoard`].
     ///
     /// This method copy [`XOBoard`] out (since [`XOBoard`] implement Copy)
@@ -0,0 +1,258 @@
+use crate::board::XOBoard;
+use crate::token::{XOToken, XOTokenWinState};
+use crate::xo_pos::XOPos;
+
+/// One of the 8 lines of three cells that win the game
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Line {
+    /// cells 0, 1, 2
+    TopRow,
+    /// cells 3, 4, 5
+    MiddleRow,
+    /// cells 6, 7, 8
+    BottomRow,
+    /// cells 0, 3, 6
+    LeftColumn,
+    /// cells 1, 4, 7
+    MiddleColumn,
+    /// cells 2, 5, 8
+    RightColumn,
+    /// cells 0, 4, 8
+    Diagonal,
+    /// cells 2, 4, 6
+    AntiDiagonal,
+}
+
+use Line::*;
+
+impl Line {
+    /// Every line, rows then columns then diagonals
+    pub const ALL: [Line; 8] = [
+        TopRow,
+        MiddleRow,
+        BottomRow,
+        LeftColumn,
+        MiddleColumn,
+        RightColumn,
+        Diagonal,
+        AntiDiagonal,
+    ];
+
+    /// Bit mask of the cells, using the same cell layout as [`XOBoard::x_bit`]
+    ///
+    /// [`XOBoard::x_bit`]: struct.XOBoard.html#method.x_bit
+    pub fn bit(self) -> u32 {
+        match self {
+            TopRow => 0o007,
+            MiddleRow => 0o070,
+            BottomRow => 0o700,
+            LeftColumn => 0o111,
+            MiddleColumn => 0o222,
+            RightColumn => 0o444,
+            Diagonal => 0o421,
+            AntiDiagonal => 0o124,
+        }
+    }
+
+    /// The three cells of the line in index order
+    pub fn cells(self) -> [XOPos; 3] {
+        let bit = self.bit();
+        let first = bit.trailing_zeros();
+        let last = 31 - bit.leading_zeros();
+        let middle = (bit & !(0b1 << first | 0b1 << last)).trailing_zeros();
+        [
+            XOPos::new_unchecked(first),
+            XOPos::new_unchecked(middle),
+            XOPos::new_unchecked(last),
+        ]
+    }
+
+    /// Check if `pos` is one of the cells of the line
+    pub fn contains(self, pos: XOPos) -> bool {
+        self.bit() >> pos.as_index() & 0b1 == 1
+    }
+}
+
+/// Iterator through a set of [`Line`], in the order of [`Line::ALL`]
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOBoard::winning_lines`]
+/// or [`XOBoard::lines_of`]
+///
+/// [`Line`]: enum.Line.html
+/// [`Line::ALL`]: enum.Line.html#associatedconstant.ALL
+/// [`XOBoard::winning_lines`]: struct.XOBoard.html#method.winning_lines
+/// [`XOBoard::lines_of`]: struct.XOBoard.html#method.lines_of
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LineIter {
+    /// bit `i` is set if `Line::ALL[i]` is in the set
+    lines: u8,
+}
+
+impl Iterator for LineIter {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.lines == 0 {
+            return None;
+        }
+
+        let index = self.lines.trailing_zeros();
+        self.lines &= self.lines - 1;
+        Some(Line::ALL[index as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.lines.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for LineIter {}
+
+fn completed_lines(bits: u32) -> LineIter {
+    let mut lines = 0;
+    for (index, line) in Line::ALL.iter().enumerate() {
+        if bits & line.bit() == line.bit() {
+            lines |= 0b1 << index;
+        }
+    }
+    LineIter { lines }
+}
+
+impl XOBoard {
+    /// Iterate through every line completed by `token`
+    pub fn lines_of(self, token: XOToken) -> LineIter {
+        completed_lines(self.token_bit(token))
+    }
+
+    /// Iterate through every line completed by either X or O,
+    /// the lines that decided the game when [`XOBoard::evaluate_winner`] report a winner
+    ///
+    /// Under [`XORules::Misere`] these are the loser's lines
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{Line, XOBoard, XOPos};
+    ///
+    /// let board = XOBoard::empty()
+    ///     .play(XOPos::index(0)?)?
+    ///     .play(XOPos::index(3)?)?
+    ///     .play(XOPos::index(4)?)?
+    ///     .play(XOPos::index(5)?)?
+    ///     .play(XOPos::index(8)?)?;
+    ///
+    /// let lines: Vec<_> = board.winning_lines().collect();
+    /// assert_eq!(lines, vec![Line::Diagonal]);
+    /// assert_eq!(lines[0].cells(), [XOPos::index(0)?, XOPos::index(4)?, XOPos::index(8)?]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::evaluate_winner`]: struct.XOBoard.html#method.evaluate_winner
+    /// [`XORules::Misere`]: enum.XORules.html#variant.Misere
+    pub fn winning_lines(self) -> LineIter {
+        LineIter {
+            lines: completed_lines(self.x_bit()).lines | completed_lines(self.o_bit()).lines,
+        }
+    }
+}
+
+/// Detailed ending of xo game, returned by [`XO::play_detailed`]
+///
+/// [`XO::play_detailed`]: struct.XO.html#method.play_detailed
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct XOGameOutcome {
+    win_state: XOTokenWinState,
+    lines: LineIter,
+}
+
+impl XOGameOutcome {
+    pub(crate) fn new(board: XOBoard, win_state: XOTokenWinState) -> Self {
+        XOGameOutcome {
+            win_state,
+            lines: board.winning_lines(),
+        }
+    }
+
+    /// Type of game ending, the same as returned by [`XO::play`]
+    ///
+    /// [`XO::play`]: struct.XO.html#method.play
+    pub fn win_state(self) -> XOTokenWinState {
+        self.win_state
+    }
+
+    /// Lines that decided the game (empty on stalemate)
+    ///
+    /// They are completed by the winner, or by the loser under [`XORules::Misere`]
+    ///
+    /// [`XORules::Misere`]: enum.XORules.html#variant.Misere
+    pub fn lines(self) -> LineIter {
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn line_cells() {
+        for line in Line::ALL.iter() {
+            let cells = line.cells();
+            let bit = cells.iter().fold(0, |bit, pos| bit | 0b1 << pos.as_index());
+            assert_eq!(bit, line.bit());
+            assert!(cells.iter().all(|&pos| line.contains(pos)));
+        }
+    }
+
+    #[test]
+    fn double_line() -> XOResult {
+        /*
+        X X X
+        O O X
+        O O X
+        */
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(3)?)?
+            .play(XOPos::index(1)?)?
+            .play(XOPos::index(4)?)?
+            .play(XOPos::index(5)?)?
+            .play(XOPos::index(6)?)?
+            .play(XOPos::index(8)?)?
+            .play(XOPos::index(7)?)?
+            .play(XOPos::index(2)?)?;
+        assert_eq!(board.win_state(), Some(XOTokenWinState::X));
+        assert_eq!(
+            board.winning_lines().collect::<Vec<_>>(),
+            vec![TopRow, RightColumn]
+        );
+        assert_eq!(board.lines_of(XOToken::O).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn misere_play_detailed() -> XOResult {
+        use crate::board::XORules;
+        use crate::XO;
+
+        let mut game = XO::with_rules(XORules::Misere);
+        for &index in [0, 3, 1, 4].iter() {
+            assert_eq!(game.play_detailed(XOPos::index(index)?)?, None);
+        }
+
+        // X complete the top row and lose
+        let outcome = game.play_detailed(XOPos::index(2)?)?.unwrap();
+        assert_eq!(outcome.win_state(), XOTokenWinState::O);
+        assert_eq!(outcome.lines().collect::<Vec<_>>(), vec![TopRow]);
+        assert_eq!(
+            game.board().winning_lines().collect::<Vec<_>>(),
+            vec![TopRow]
+        );
+        Ok(())
+    }
+}
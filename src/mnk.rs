@@ -0,0 +1,414 @@
+//! Generic m,n,k-game: `K` in a row on a board of `M` rows and `N` columns
+//!
+//! [`MNKBoard`] is a bitboard like [`XOBoard`], backed by a `u128` per player
+//! so any board with `M * (N + 1) <= 128` is supported (up to 10x10 or 8x14).
+//! Every row has an extra always-empty bit at its end, so shifting a row past its last column
+//! land on an empty cell and lines are detected by shift-and without any mask.
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::mnk::{MNKBoard, MNKPos};
+//! use bitboard_xo::XOTokenWinState;
+//!
+//! // 4 in a row on a 5x5 board
+//! let mut board = MNKBoard::<5, 5, 4>::empty();
+//! for &(row, col) in [(0, 0), (1, 0), (1, 1), (2, 0), (2, 2), (3, 0), (3, 3)].iter() {
+//!     board = board.play(MNKPos::row_col(row, col)?)?;
+//! }
+//! assert_eq!(board.win_state(), Some(XOTokenWinState::X));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`MNKBoard`]: struct.MNKBoard.html
+//! [`XOBoard`]: ../struct.XOBoard.html
+
+use crate::board::{XOGameError, XOGameResult};
+use crate::token::{XOToken, XOTokenWinState};
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::iter;
+
+custom_error! {
+/// Error use when attempting to constructing [`MNKPos`] outside of the board
+///
+/// [`MNKPos`]: struct.MNKPos.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub MNKPosError
+    InvalidIndex {index: u32, size: u32} =
+        "invalid index: {index}, expecting integer in [0, {size}) counting in book reading direction",
+    InvalidRow {row: u32, rows: u32} = "invalid row: {row}, expecting integer in [0, {rows})",
+    InvalidCol {col: u32, cols: u32} = "invalid column: {col}, expecting integer in [0, {cols})",
+}
+
+/// Position on a board of `M` rows and `N` columns
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MNKPos<const M: usize, const N: usize> {
+    index: u32,
+}
+
+impl<const M: usize, const N: usize> MNKPos<M, N> {
+    /// Create [`MNKPos`] from index, starting 0 at top left corner
+    /// and counting up in book reading direction
+    ///
+    /// # Error
+    /// Return [`MNKPosError::InvalidIndex`] if `index` >= `M * N`
+    ///
+    /// [`MNKPos`]: struct.MNKPos.html
+    /// [`MNKPosError::InvalidIndex`]: enum.MNKPosError.html#variant.InvalidIndex
+    pub fn index(index: u32) -> Result<Self, MNKPosError> {
+        let size = (M * N) as u32;
+        if index >= size {
+            return Err(MNKPosError::InvalidIndex { index, size });
+        }
+        Ok(MNKPos { index })
+    }
+
+    /// Create [`MNKPos`] from (row, column) position, (0, 0) being the top left corner
+    ///
+    /// # Error
+    /// Return [`MNKPosError::InvalidRow`] if `row` >= `M`
+    /// or [`MNKPosError::InvalidCol`] if `col` >= `N`
+    ///
+    /// [`MNKPos`]: struct.MNKPos.html
+    /// [`MNKPosError::InvalidRow`]: enum.MNKPosError.html#variant.InvalidRow
+    /// [`MNKPosError::InvalidCol`]: enum.MNKPosError.html#variant.InvalidCol
+    pub fn row_col(row: u32, col: u32) -> Result<Self, MNKPosError> {
+        if row >= M as u32 {
+            return Err(MNKPosError::InvalidRow {
+                row,
+                rows: M as u32,
+            });
+        }
+        if col >= N as u32 {
+            return Err(MNKPosError::InvalidCol {
+                col,
+                cols: N as u32,
+            });
+        }
+        Ok(MNKPos {
+            index: row * N as u32 + col,
+        })
+    }
+
+    /// Retrieve index inside this [`MNKPos`], guaranteed to be in [0, `M * N`)
+    /// counting in book reading direction
+    ///
+    /// [`MNKPos`]: struct.MNKPos.html
+    pub fn as_index(self) -> u32 {
+        self.index
+    }
+
+    /// Retrieve row of this [`MNKPos`] (0 for top row), guaranteed to be in [0, `M`)
+    ///
+    /// [`MNKPos`]: struct.MNKPos.html
+    pub fn row(self) -> u32 {
+        self.index / N as u32
+    }
+
+    /// Retrieve column of this [`MNKPos`] (0 for left column), guaranteed to be in [0, `N`)
+    ///
+    /// [`MNKPos`]: struct.MNKPos.html
+    pub fn col(self) -> u32 {
+        self.index % N as u32
+    }
+
+    /// Bit of this position in the padded layout of [`MNKBoard`]
+    ///
+    /// [`MNKBoard`]: struct.MNKBoard.html
+    fn bit(self) -> u128 {
+        0b1 << (self.row() as usize * (N + 1) + self.col() as usize)
+    }
+}
+
+/// Bitboard of a m,n,k-game with `M` rows, `N` columns and `K` in a row to win
+///
+/// X always start, and like [`XOBoard`] every method return a new board.
+///
+/// Using a board with `M * (N + 1) > 128` or any zero dimension fail to compile
+/// (once the board is constructed)
+///
+/// ```compile_fail
+/// use bitboard_xo::mnk::MNKBoard;
+///
+/// let board = MNKBoard::<12, 12, 5>::empty();
+/// ```
+///
+/// [`XOBoard`]: ../struct.XOBoard.html
+#[must_use = "MNKBoard's method doesn't mutate but return a new board"]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MNKBoard<const M: usize, const N: usize, const K: usize> {
+    x_bit: u128,
+    o_bit: u128,
+    turn: XOToken,
+    win_state: Option<XOTokenWinState>,
+}
+
+impl<const M: usize, const N: usize, const K: usize> MNKBoard<M, N, K> {
+    /// Bits between vertically adjacent cells
+    const WIDTH: usize = N + 1;
+
+    const CHECK_SIZE: () = assert!(
+        M > 0 && N > 0 && K > 0 && M * (N + 1) <= 128,
+        "MNKBoard need non zero dimension and M * (N + 1) <= 128"
+    );
+
+    /// Every cell of the board (without padding)
+    const FULL: u128 = {
+        let mut full = 0;
+        let mut row = 0;
+        while row < M {
+            full |= ((0b1 << N) - 1) << (row * (N + 1));
+            row += 1;
+        }
+        full
+    };
+
+    /// Construct an empty board with X to play
+    pub fn empty() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::CHECK_SIZE;
+        MNKBoard {
+            x_bit: 0,
+            o_bit: 0,
+            turn: XOToken::X,
+            win_state: None,
+        }
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(self) -> XOToken {
+        self.turn
+    }
+
+    /// Get information about who (if any) won the game
+    pub fn win_state(self) -> Option<XOTokenWinState> {
+        self.win_state
+    }
+
+    pub fn game_ended(self) -> bool {
+        self.win_state.is_some()
+    }
+
+    /// Get token at `pos`, `None` if empty
+    pub fn get(self, pos: MNKPos<M, N>) -> Option<XOToken> {
+        if self.x_bit & pos.bit() != 0 {
+            Some(XOToken::X)
+        } else if self.o_bit & pos.bit() != 0 {
+            Some(XOToken::O)
+        } else {
+            None
+        }
+    }
+
+    /// Play at `pos` for the current player, the same rule as [`XOBoard::play`]
+    ///
+    /// # Error
+    /// Return [`XOGameError::GameEndedError`] if the game has ended,
+    /// or [`XOGameError::AlreadyPlayedError`] (with [`MNKPos::as_index`]) if `pos` isn't empty
+    ///
+    /// [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+    /// [`XOGameError::GameEndedError`]: ../enum.XOGameError.html#variant.GameEndedError
+    /// [`XOGameError::AlreadyPlayedError`]: ../enum.XOGameError.html#variant.AlreadyPlayedError
+    /// [`MNKPos::as_index`]: struct.MNKPos.html#method.as_index
+    pub fn play(self, pos: MNKPos<M, N>) -> XOGameResult<Self> {
+        if self.game_ended() {
+            return Err(XOGameError::GameEndedError);
+        }
+        if (self.x_bit | self.o_bit) & pos.bit() != 0 {
+            return Err(XOGameError::AlreadyPlayedError {
+                index: pos.as_index(),
+            });
+        }
+
+        let mut board = self;
+        let mover_bit = match self.turn {
+            XOToken::X => {
+                board.x_bit |= pos.bit();
+                board.x_bit
+            }
+            XOToken::O => {
+                board.o_bit |= pos.bit();
+                board.o_bit
+            }
+        };
+        // the game was ongoing, so only a line of the player who just played can appear
+        board.win_state = if Self::has_line(mover_bit) {
+            Some(XOTokenWinState::from(self.turn))
+        } else if board.free_bit() == 0 {
+            Some(XOTokenWinState::Stale)
+        } else {
+            None
+        };
+        board.turn = self.turn.opposite_token();
+        Ok(board)
+    }
+
+    /// Evaluate win state from the cells
+    pub fn evaluate_winner(self) -> Option<XOTokenWinState> {
+        if Self::has_line(self.x_bit) {
+            Some(XOTokenWinState::X)
+        } else if Self::has_line(self.o_bit) {
+            Some(XOTokenWinState::O)
+        } else if self.free_bit() == 0 {
+            Some(XOTokenWinState::Stale)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate through positions that can be played, empty once the game has ended
+    pub fn legal_moves(self) -> impl Iterator<Item = MNKPos<M, N>> {
+        let mut free = if self.game_ended() {
+            0
+        } else {
+            self.free_bit()
+        };
+        iter::from_fn(move || {
+            if free == 0 {
+                return None;
+            }
+
+            let bit = free.trailing_zeros() as usize;
+            free &= free - 1;
+            let index = bit / (N + 1) * N + bit % (N + 1);
+            Some(MNKPos {
+                index: index as u32,
+            })
+        })
+    }
+
+    fn free_bit(self) -> u128 {
+        !(self.x_bit | self.o_bit) & Self::FULL
+    }
+
+    /// Check for `K` consecutive bits in the row, column and both diagonal directions
+    fn has_line(bits: u128) -> bool {
+        [1, Self::WIDTH, Self::WIDTH + 1, Self::WIDTH - 1]
+            .iter()
+            .any(|&step| {
+                let mut run = bits;
+                // after i shifts, `run` mark the first cell of every run of i + 1 cells
+                for _ in 1..K {
+                    run &= run >> step;
+                }
+                run != 0
+            })
+    }
+}
+
+impl<const M: usize, const N: usize, const K: usize> Default for MNKBoard<M, N, K> {
+    fn default() -> Self {
+        MNKBoard::empty()
+    }
+}
+
+impl<const M: usize, const N: usize, const K: usize> Display for MNKBoard<M, N, K> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        if let Some(winner) = self.win_state() {
+            match winner {
+                XOTokenWinState::Stale => writeln!(f, "Game Ended In Stale Mate")?,
+                XOTokenWinState::X | XOTokenWinState::O => {
+                    writeln!(f, "Game Ended: {}'s winner", winner)?
+                }
+            }
+        } else {
+            writeln!(f, "{}'s Turn", self.turn())?;
+        }
+
+        for row in 0..M as u32 {
+            for col in 0..N as u32 {
+                match self.get(MNKPos {
+                    index: row * N as u32 + col,
+                }) {
+                    Some(XOToken::X) => f.write_char('X')?,
+                    Some(XOToken::O) => f.write_char('O')?,
+                    None => f.write_char('.')?,
+                }
+            }
+            f.write_char('\n')?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XOBoard;
+    use crate::xo_pos::XOPos;
+    use crate::XOResult;
+    use std::collections::HashSet;
+
+    #[test]
+    fn agree_with_xo_board() -> XOResult {
+        // play every reachable 3x3 position with both boards
+        let mut seen = HashSet::new();
+        let mut stack = vec![(XOBoard::empty(), MNKBoard::<3, 3, 3>::empty())];
+        while let Some((board, mnk)) = stack.pop() {
+            if !seen.insert(board) {
+                continue;
+            }
+            assert_eq!(mnk.win_state(), board.win_state());
+            assert_eq!(mnk.evaluate_winner(), board.evaluate_winner());
+            for index in 0..9 {
+                let played = board.play(XOPos::index(index)?);
+                let mnk_played = mnk.play(MNKPos::index(index)?);
+                assert_eq!(played.as_ref().err(), mnk_played.as_ref().err());
+                if let (Ok(played), Ok(mnk_played)) = (played, mnk_played) {
+                    stack.push((played, mnk_played));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn no_wrap_around() -> XOResult {
+        // 3 in a row on a 4x4 board, X play the end of row 0 and start of row 1
+        let mut board = MNKBoard::<4, 4, 3>::empty();
+        for &(row, col) in [(0, 2), (3, 0), (0, 3), (3, 2), (1, 0)].iter() {
+            board = board.play(MNKPos::row_col(row, col)?)?;
+        }
+        assert_eq!(board.win_state(), None);
+
+        // anti diagonal across the row boundary
+        let mut board = MNKBoard::<4, 4, 3>::empty();
+        for &(row, col) in [(0, 1), (3, 3), (1, 0), (3, 2), (1, 3)].iter() {
+            board = board.play(MNKPos::row_col(row, col)?)?;
+        }
+        assert_eq!(board.win_state(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn bounds() {
+        assert_eq!(
+            MNKPos::<7, 7>::row_col(7, 0),
+            Err(MNKPosError::InvalidRow { row: 7, rows: 7 })
+        );
+        assert_eq!(
+            MNKPos::<4, 5>::index(20),
+            Err(MNKPosError::InvalidIndex {
+                index: 20,
+                size: 20
+            })
+        );
+        assert_eq!(MNKPos::<4, 5>::index(19).map(MNKPos::row), Ok(3));
+        assert_eq!(MNKBoard::<10, 10, 5>::empty().legal_moves().count(), 100);
+    }
+
+    #[test]
+    fn legal_moves_in_index_order() -> XOResult {
+        let board = MNKBoard::<4, 5, 4>::empty()
+            .play(MNKPos::row_col(0, 4)?)?
+            .play(MNKPos::row_col(3, 0)?)?;
+        let indices: Vec<u32> = board.legal_moves().map(MNKPos::as_index).collect();
+        let expected: Vec<u32> = (0..20).filter(|&index| index != 4 && index != 15).collect();
+        assert_eq!(indices, expected);
+        Ok(())
+    }
+}
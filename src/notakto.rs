@@ -0,0 +1,585 @@
+//! Notakto: xo on several boards where both players place X, and killing the last board lose
+//!
+//! A board dies once it has three in a row, and dead boards can't be played anymore.
+//! Each board is a [`XOBoard`] with only X bits set.
+//!
+//! # Misère quotient
+//! Notakto is a misère sum of its boards, so a position can be solved without search
+//! by multiplying the value of every board in the misère quotient found by Plambeck and Whitehead:
+//!
+//! ```text
+//! Q = < a, b, c, d | a² = 1, b³ = b, b²c = c, c³ = ac², b²d = d, cd = ad, d² = c² >
+//! ```
+//!
+//! which has 18 elements. The player to move lose exactly when the product is one of
+//! `a`, `b²`, `bc` or `c²` (see [`NotaktoValue`]).
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::notakto::{Notakto, NotaktoPos, NotaktoValue};
+//! use bitboard_xo::{Player, XOPos};
+//!
+//! // the first player win a single board by playing at the center
+//! let game = Notakto::new(1);
+//! assert_eq!(game.value(), NotaktoValue::C);
+//! assert_eq!(game.winning_moves(), vec![NotaktoPos::new(0, XOPos::index(4)?)]);
+//!
+//! // but lose with two boards
+//! let mut game = Notakto::new(2);
+//! assert!(game.value().is_p_position());
+//! assert!(game.winning_moves().is_empty());
+//!
+//! game.play(NotaktoPos::new(0, XOPos::index(4)?))?;
+//! assert_eq!(game.turn(), Player::Second);
+//! assert!(!game.value().is_p_position());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`XOBoard`]: ../struct.XOBoard.html
+//! [`NotaktoValue`]: struct.NotaktoValue.html
+
+use crate::board::{XOBoard, XOGameError};
+use crate::token::{Player, XOToken};
+use crate::xo_pos::XOPos;
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::ops::{Mul, MulAssign};
+
+custom_error! {
+/// Error use when trying to do invalid play in [`Notakto`]
+///
+/// [`Notakto`]: struct.Notakto.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub NotaktoError
+    /// Error of playing on a board that doesn't exist
+    BoardIndexError{board: usize, boards: usize} =
+        "invalid board index {board}, expecting index less than {boards}",
+    /// Error of playing on a board that already has three in a row
+    DeadBoardError{board: usize} = "attempt to play on board {board} which is dead",
+    /// Error of playing on an occupied cell or after the game ended
+    GameError{source: XOGameError} = "Error occurred when trying to play (make a move)",
+}
+pub type NotaktoResult<T = ()> = Result<T, NotaktoError>;
+
+/// Element of Notakto's misère quotient, as the word `a^i b^j c^k d^l` in normal form
+///
+/// Values multiply like the boards are added together, see [module documentation].
+///
+/// [module documentation]: index.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NotaktoValue {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+}
+
+impl NotaktoValue {
+    /// Identity, the value of a dead board
+    pub const ONE: NotaktoValue = NotaktoValue::word(0, 0, 0, 0);
+    /// Value of 17 boards (up to symmetry) that lose for the player to move on their own,
+    /// such as X at two edges next to the same corner
+    pub const A: NotaktoValue = NotaktoValue::word(1, 0, 0, 0);
+    /// Value of 15 boards (up to symmetry), such as X at both corners of a side
+    pub const B: NotaktoValue = NotaktoValue::word(0, 1, 0, 0);
+    /// Value of an empty board
+    pub const C: NotaktoValue = NotaktoValue::word(0, 0, 1, 0);
+    /// Value of a board with X at a corner and an edge next to it,
+    /// the only board (up to symmetry) taking this value
+    pub const D: NotaktoValue = NotaktoValue::word(0, 0, 0, 1);
+
+    const fn word(a: u8, b: u8, c: u8, d: u8) -> NotaktoValue {
+        NotaktoValue { a, b, c, d }
+    }
+
+    /// Rewrite with the relations of the quotient until reaching the normal form
+    fn reduce(self) -> NotaktoValue {
+        let NotaktoValue {
+            mut a,
+            mut b,
+            mut c,
+            mut d,
+        } = self;
+        loop {
+            let before = (a, b, c, d);
+            a %= 2;
+            if b >= 3 {
+                b -= 2; // b³ = b
+            }
+            if b >= 2 && (c >= 1 || d >= 1) {
+                b -= 2; // b²c = c, b²d = d
+            }
+            if c >= 3 {
+                c -= 1; // c³ = ac²
+                a += 1;
+            }
+            if c >= 1 && d >= 1 {
+                c -= 1; // cd = ad
+                a += 1;
+            }
+            if d >= 2 {
+                d -= 2; // d² = c²
+                c += 2;
+            }
+            a %= 2;
+            if (a, b, c, d) == before {
+                return NotaktoValue { a, b, c, d };
+            }
+        }
+    }
+
+    /// Value of a single board from its X cells, [`NotaktoValue::ONE`] if the board is dead
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::notakto::NotaktoValue;
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let center = XOBoard::empty().play(XOPos::index(4)?)?;
+    /// assert_eq!(NotaktoValue::of_board(center), NotaktoValue::C * NotaktoValue::C);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`NotaktoValue::ONE`]: struct.NotaktoValue.html#associatedconstant.ONE
+    pub fn of_board(board: XOBoard) -> NotaktoValue {
+        let (canonical, _) = XOBoard::new(board.x_bit()).canonical();
+        // values of the 46 live boards up to symmetry, keyed by their canonical X bits
+        match canonical.x_bit() {
+            0o000 => NotaktoValue::C,
+            0o020 => NotaktoValue::C * NotaktoValue::C,
+            0o003 => NotaktoValue::D,
+            0o016 | 0o051 | 0o106 => NotaktoValue::A * NotaktoValue::D,
+            0o023 | 0o032 | 0o105 | 0o116 | 0o152 => NotaktoValue::A * NotaktoValue::B,
+            0o005 | 0o013 | 0o014 | 0o021 | 0o022 | 0o035 | 0o036 | 0o052 | 0o055 | 0o143
+            | 0o145 | 0o156 | 0o161 | 0o162 | 0o253 => NotaktoValue::B,
+            0o012 | 0o015 | 0o025 | 0o033 | 0o034 | 0o050 | 0o053 | 0o104 | 0o141 | 0o146
+            | 0o154 | 0o163 | 0o252 | 0o255 | 0o345 | 0o356 | 0o505 => NotaktoValue::A,
+            // a single X at a corner or an edge, 0o142, and dead boards
+            _ => NotaktoValue::ONE,
+        }
+    }
+
+    /// Whether the player to move lose against perfect play,
+    /// which is when the value is one of `a`, `b²`, `bc` or `c²`
+    pub fn is_p_position(self) -> bool {
+        matches!(
+            (self.a, self.b, self.c, self.d),
+            (1, 0, 0, 0) | (0, 2, 0, 0) | (0, 1, 1, 0) | (0, 0, 2, 0)
+        )
+    }
+}
+
+impl Mul for NotaktoValue {
+    type Output = NotaktoValue;
+
+    fn mul(self, rhs: NotaktoValue) -> NotaktoValue {
+        NotaktoValue::word(
+            self.a + rhs.a,
+            self.b + rhs.b,
+            self.c + rhs.c,
+            self.d + rhs.d,
+        )
+        .reduce()
+    }
+}
+
+impl MulAssign for NotaktoValue {
+    fn mul_assign(&mut self, rhs: NotaktoValue) {
+        *self = *self * rhs;
+    }
+}
+
+impl Default for NotaktoValue {
+    fn default() -> Self {
+        NotaktoValue::ONE
+    }
+}
+
+impl Display for NotaktoValue {
+    /// Show the normal form word, such as `1`, `ab` or `bc²`
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        if *self == NotaktoValue::ONE {
+            return f.write_char('1');
+        }
+        for &(letter, power) in [('a', self.a), ('b', self.b), ('c', self.c), ('d', self.d)].iter()
+        {
+            match power {
+                0 => {}
+                1 => f.write_char(letter)?,
+                _ => write!(f, "{}²", letter)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Position in notakto, a cell of one of the boards
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NotaktoPos {
+    board: usize,
+    cell: XOPos,
+}
+
+impl NotaktoPos {
+    /// Create position of `cell` in the board at index `board`
+    pub fn new(board: usize, cell: XOPos) -> Self {
+        NotaktoPos { board, cell }
+    }
+
+    /// Index of the board
+    pub fn board(self) -> usize {
+        self.board
+    }
+
+    /// Position of the cell in its board
+    pub fn cell(self) -> XOPos {
+        self.cell
+    }
+}
+
+/// Representing notakto game, see [module documentation]
+///
+/// [module documentation]: index.html
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Notakto {
+    boards: Vec<XOBoard>,
+    turn: Player,
+}
+
+impl Notakto {
+    /// Construct a new game with `boards` empty boards and [`Player::First`] to move
+    ///
+    /// # Panics
+    /// Panic if `boards` is zero
+    ///
+    /// [`Player::First`]: ../enum.Player.html#variant.First
+    pub fn new(boards: usize) -> Self {
+        assert!(boards > 0, "notakto need at least one board");
+        Notakto {
+            boards: vec![XOBoard::empty(); boards],
+            turn: Player::First,
+        }
+    }
+
+    /// Construct a game from the X cells of `boards`, with `turn` to move
+    ///
+    /// O cells, turn and win state bits of the boards are ignored.
+    ///
+    /// # Panics
+    /// Panic if `boards` is empty
+    pub fn from_boards(boards: &[XOBoard], turn: Player) -> Self {
+        assert!(!boards.is_empty(), "notakto need at least one board");
+        let boards = boards
+            .iter()
+            .map(|board| {
+                let board = XOBoard::new(board.x_bit());
+                board.set_win_state(board.evaluate_winner())
+            })
+            .collect();
+        Notakto { boards, turn }
+    }
+
+    /// Retrieve every board, dead boards have [`XOTokenWinState::X`] as their win state
+    ///
+    /// [`XOTokenWinState::X`]: ../enum.XOTokenWinState.html#variant.X
+    pub fn boards(&self) -> &[XOBoard] {
+        &self.boards
+    }
+
+    /// Player who going to play
+    pub fn turn(&self) -> Player {
+        self.turn
+    }
+
+    /// Number of boards that can still be played
+    pub fn alive_boards(&self) -> usize {
+        self.boards
+            .iter()
+            .filter(|board| !board.game_ended())
+            .count()
+    }
+
+    /// The winner once every board is dead, which is the player who didn't kill the last board
+    pub fn winner(&self) -> Option<Player> {
+        if self.alive_boards() == 0 {
+            // the player who killed the last board passed the turn to the winner
+            Some(self.turn)
+        } else {
+            None
+        }
+    }
+
+    /// Play the game! This turn's player will place X at position `pos`
+    ///
+    /// Return the winner if the game ended after this move
+    ///
+    /// # Error
+    /// Return [`NotaktoError::BoardIndexError`] if the board doesn't exist,
+    /// [`NotaktoError::DeadBoardError`] if the board is dead, or
+    /// [`NotaktoError::GameError`] if the cell is occupied or the game has ended
+    ///
+    /// [`NotaktoError::BoardIndexError`]: enum.NotaktoError.html#variant.BoardIndexError
+    /// [`NotaktoError::DeadBoardError`]: enum.NotaktoError.html#variant.DeadBoardError
+    /// [`NotaktoError::GameError`]: enum.NotaktoError.html#variant.GameError
+    pub fn play(&mut self, pos: NotaktoPos) -> NotaktoResult<Option<Player>> {
+        if self.winner().is_some() {
+            return Err(XOGameError::GameEndedError.into());
+        }
+        let board = match self.boards.get(pos.board) {
+            Some(board) => *board,
+            None => {
+                return Err(NotaktoError::BoardIndexError {
+                    board: pos.board,
+                    boards: self.boards.len(),
+                })
+            }
+        };
+        if board.game_ended() {
+            return Err(NotaktoError::DeadBoardError { board: pos.board });
+        }
+
+        // every board stay on X's turn
+        self.boards[pos.board] = board.play(pos.cell)?.swap_turn();
+        self.turn = self.turn.opposite();
+        Ok(self.winner())
+    }
+
+    /// Every position that can be played, in board then cell order
+    pub fn legal_moves(&self) -> Vec<NotaktoPos> {
+        self.boards
+            .iter()
+            .enumerate()
+            .flat_map(|(index, board)| {
+                board
+                    .legal_moves()
+                    .map(move |cell| NotaktoPos::new(index, cell))
+            })
+            .collect()
+    }
+
+    /// Value of the position in the misère quotient, the product of every board's value
+    pub fn value(&self) -> NotaktoValue {
+        self.boards.iter().fold(NotaktoValue::ONE, |value, &board| {
+            value * NotaktoValue::of_board(board)
+        })
+    }
+
+    /// Every move leaving the opponent in a losing position, empty if the player to move lose
+    pub fn winning_moves(&self) -> Vec<NotaktoPos> {
+        let values: Vec<NotaktoValue> = self
+            .boards
+            .iter()
+            .map(|&board| NotaktoValue::of_board(board))
+            .collect();
+        self.legal_moves()
+            .into_iter()
+            .filter(|pos| {
+                let played = self.boards[pos.board].set(XOToken::X, pos.cell);
+                let value = values
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, _)| index != pos.board)
+                    .fold(NotaktoValue::of_board(played), |value, (_, &other)| {
+                        value * other
+                    });
+                value.is_p_position()
+            })
+            .collect()
+    }
+}
+
+impl Display for Notakto {
+    /// Show the boards side by side, dead boards with `#` at empty cells
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self.winner() {
+            Some(winner) => writeln!(f, "Game Ended: {}'s winner", winner)?,
+            None => writeln!(f, "{}'s Turn", self.turn)?,
+        }
+
+        for row in 0..3 {
+            for (index, board) in self.boards.iter().enumerate() {
+                if index != 0 {
+                    f.write_char(' ')?;
+                }
+                for col in 0..3 {
+                    let cell = XOPos::new_unchecked(row * 3 + col);
+                    match board.token_at(cell) {
+                        Some(_) => f.write_char('X')?,
+                        None if board.game_ended() => f.write_char('#')?,
+                        None => f.write_char('.')?,
+                    }
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+    use std::collections::{HashMap, HashSet};
+
+    /// Whether the player to move lose, by searching every move of the sum of `boards`
+    fn brute_force_p_position(boards: &[u32], memo: &mut HashMap<Vec<u32>, bool>) -> bool {
+        let mut key = boards.to_vec();
+        key.sort_unstable();
+        if let Some(&is_p) = memo.get(&key) {
+            return is_p;
+        }
+
+        // with no board left the previous player killed the last board and lost
+        let mut is_p = !key.is_empty();
+        'search: for (index, &bits) in key.iter().enumerate() {
+            for cell in 0..9 {
+                if bits >> cell & 0b1 == 1 {
+                    continue;
+                }
+                let played = XOBoard::new(bits | 0b1 << cell);
+                let mut rest: Vec<u32> = key.clone();
+                if played.evaluate_winner().is_some() {
+                    rest.remove(index);
+                } else {
+                    rest[index] = played.canonical().0.x_bit();
+                }
+                if brute_force_p_position(&rest, memo) {
+                    is_p = false;
+                    break 'search;
+                }
+            }
+        }
+        memo.insert(key, is_p);
+        is_p
+    }
+
+    #[test]
+    fn quotient_has_18_elements() {
+        let generators = [
+            NotaktoValue::A,
+            NotaktoValue::B,
+            NotaktoValue::C,
+            NotaktoValue::D,
+        ];
+        let mut elements = HashSet::new();
+        let mut stack = vec![NotaktoValue::ONE];
+        while let Some(value) = stack.pop() {
+            if elements.insert(value) {
+                stack.extend(generators.iter().map(|&generator| value * generator));
+            }
+        }
+        assert_eq!(elements.len(), 18);
+        assert_eq!(
+            elements
+                .iter()
+                .filter(|value| value.is_p_position())
+                .count(),
+            4
+        );
+        assert_eq!(
+            (NotaktoValue::B * NotaktoValue::C * NotaktoValue::C).to_string(),
+            "bc²"
+        );
+    }
+
+    #[test]
+    fn quotient_agree_with_search() {
+        let live: Vec<u32> = (0..0b1_000_000_000)
+            .map(XOBoard::new)
+            .filter(|board| board.evaluate_winner().is_none())
+            .map(|board| board.canonical().0.x_bit())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(live.len(), 46);
+
+        let mut memo = HashMap::new();
+        let value = |boards: &[u32]| {
+            boards.iter().fold(NotaktoValue::ONE, |value, &bits| {
+                value * NotaktoValue::of_board(XOBoard::new(bits))
+            })
+        };
+        for (i, &first) in live.iter().enumerate() {
+            for (j, &second) in live.iter().enumerate().skip(i) {
+                for &third in live.iter().skip(j) {
+                    for boards in
+                        [vec![first], vec![first, second], vec![first, second, third]].iter()
+                    {
+                        assert_eq!(
+                            value(boards).is_p_position(),
+                            brute_force_p_position(boards, &mut memo),
+                            "{:?}",
+                            boards
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn play_until_dead() -> XOResult {
+        let mut game = Notakto::new(2);
+        for &(board, cell) in [(0, 0), (0, 1), (1, 4)].iter() {
+            assert_eq!(
+                game.play(NotaktoPos::new(board, XOPos::index(cell)?))?,
+                None
+            );
+        }
+        assert_eq!(
+            game.play(NotaktoPos::new(2, XOPos::index(0)?)),
+            Err(NotaktoError::BoardIndexError {
+                board: 2,
+                boards: 2
+            })
+        );
+
+        // second player kill board 0
+        game.play(NotaktoPos::new(0, XOPos::index(2)?))?;
+        assert_eq!(game.alive_boards(), 1);
+        assert_eq!(
+            game.play(NotaktoPos::new(0, XOPos::index(8)?)),
+            Err(NotaktoError::DeadBoardError { board: 0 })
+        );
+
+        // then the first player kill the last board and lose
+        game.play(NotaktoPos::new(1, XOPos::index(0)?))?;
+        game.play(NotaktoPos::new(1, XOPos::index(7)?))?;
+        assert_eq!(game.turn(), Player::First);
+        assert_eq!(
+            game.play(NotaktoPos::new(1, XOPos::index(8)?))?,
+            Some(Player::Second)
+        );
+        assert_eq!(game.winner(), Some(Player::Second));
+        assert!(game.legal_moves().is_empty());
+        assert_eq!(
+            game.play(NotaktoPos::new(1, XOPos::index(1)?)),
+            Err(NotaktoError::GameError {
+                source: XOGameError::GameEndedError
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn winning_moves_win() -> XOResult {
+        let mut game = Notakto::new(3);
+        while game.winner().is_none() {
+            let pos = match game.winning_moves().first() {
+                Some(&pos) => pos,
+                None => game.legal_moves()[0],
+            };
+            game.play(pos)?;
+        }
+        // three empty boards is a first player win
+        assert_eq!(game.winner(), Some(Player::First));
+        Ok(())
+    }
+}
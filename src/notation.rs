@@ -0,0 +1,301 @@
+//! Single line text notation for positions (XO-FEN)
+//!
+//! A position is written as 3 space separated fields, plus an optional 4th:
+//!
+//! 1. cells, rows from top to bottom separated by `/`, each cell is `X`, `O` or `.` (empty)
+//! 2. current turn's player, `x` or `o`
+//! 3. result, `-` if game didn't end yet, `x` or `o` for the winner, or `=` for stalemate
+//! 4. rules, `m` for [`XORules::Misere`], omitted for [`XORules::Normal`]
+//!
+//! ```text
+//! XX./.O./... o -
+//! XXX/OO./... o o m
+//! ```
+//!
+//! [`XORules::Misere`]: ../enum.XORules.html#variant.Misere
+//! [`XORules::Normal`]: ../enum.XORules.html#variant.Normal
+
+use crate::board::{XOBoard, XORules};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::validation::XOBoardError;
+use crate::XO;
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::str::FromStr;
+
+custom_error! {
+/// Error use when parsing invalid XO-FEN
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XONotationError
+    /// Error of not having 3 or 4 fields
+    FieldCountError{count: usize} =
+        "expected 3 or 4 space separated fields (cells, turn, result, rules), found {count}",
+    /// Error of not having exactly 3 rows
+    RowCountError{count: usize} = "expected 3 rows separated by '/', found {count}",
+    /// Error of a row not having exactly 3 cells
+    RowLengthError{row: usize, length: usize} = "expected 3 cells in row {row}, found {length}",
+    /// Error of a cell that isn't `X`, `O` or `.`
+    InvalidCellError{cell: char} = "invalid cell '{cell}', expecting one of 'X', 'O', '.'",
+    /// Error of a turn field that isn't `x` or `o`
+    InvalidTurnError{turn: String} = "invalid turn '{turn}', expecting one of 'x', 'o'",
+    /// Error of a result field that isn't `-`, `x`, `o` or `=`
+    InvalidResultError{result: String} =
+        "invalid result '{result}', expecting one of '-', 'x', 'o', '='",
+    /// Error of a rules field that isn't `m`
+    InvalidRulesError{rules: String} = "invalid rules '{rules}', expecting 'm'",
+    /// Error of a well formed notation describing an impossible position
+    BoardError{source: XOBoardError} = "notation describe an invalid board",
+}
+
+/// Display [`XOBoard`] in XO-FEN, constructed by [`XOBoard::notation`]
+///
+/// [`XOBoard`]: ../struct.XOBoard.html
+/// [`XOBoard::notation`]: ../struct.XOBoard.html#method.notation
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct XONotation {
+    board: XOBoard,
+}
+
+impl Display for XONotation {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        for (index, maybe_token) in self.board.iter().enumerate() {
+            if index != 0 && index % 3 == 0 {
+                f.write_char('/')?;
+            }
+            match maybe_token {
+                Some(XOToken::X) => f.write_char('X')?,
+                Some(XOToken::O) => f.write_char('O')?,
+                None => f.write_char('.')?,
+            }
+        }
+
+        let turn = match self.board.turn() {
+            XOToken::X => 'x',
+            XOToken::O => 'o',
+        };
+        let result = match self.board.win_state() {
+            None => '-',
+            Some(XOTokenWinState::X) => 'x',
+            Some(XOTokenWinState::O) => 'o',
+            Some(XOTokenWinState::Stale) => '=',
+        };
+        write!(f, " {} {}", turn, result)?;
+        match self.board.rules() {
+            XORules::Normal => Ok(()),
+            XORules::Misere => f.write_str(" m"),
+        }
+    }
+}
+
+impl XOBoard {
+    /// Format the board in single line [XO-FEN]
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let board = XOBoard::empty()
+    ///     .play(XOPos::index(0)?)?
+    ///     .play(XOPos::index(4)?)?
+    ///     .play(XOPos::index(1)?)?;
+    /// assert_eq!(board.notation().to_string(), "XX./.O./... o -");
+    /// assert_eq!("XX./.O./... o -".parse::<XOBoard>()?, board);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [XO-FEN]: notation/index.html
+    pub fn notation(self) -> XONotation {
+        XONotation { board: self }
+    }
+}
+
+impl FromStr for XOBoard {
+    type Err = XONotationError;
+
+    /// Parse [XO-FEN], the parsed board must pass [`XOBoard::validate`]
+    ///
+    /// [XO-FEN]: notation/index.html
+    /// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 3 && fields.len() != 4 {
+            return Err(XONotationError::FieldCountError {
+                count: fields.len(),
+            });
+        }
+
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != 3 {
+            return Err(XONotationError::RowCountError { count: rows.len() });
+        }
+        let mut cells = [None; 9];
+        for (row, cell_chars) in rows.iter().enumerate() {
+            let length = cell_chars.chars().count();
+            if length != 3 {
+                return Err(XONotationError::RowLengthError { row, length });
+            }
+            for (col, cell) in cell_chars.chars().enumerate() {
+                cells[3 * row + col] = match cell {
+                    'X' => Some(XOToken::X),
+                    'O' => Some(XOToken::O),
+                    '.' => None,
+                    _ => return Err(XONotationError::InvalidCellError { cell }),
+                };
+            }
+        }
+        let board = XOBoard::from_maybe_token_array(cells);
+
+        let board = match fields[1] {
+            "x" => board,
+            "o" => board.swap_turn(),
+            turn => {
+                return Err(XONotationError::InvalidTurnError {
+                    turn: turn.to_string(),
+                })
+            }
+        };
+
+        let win_state = match fields[2] {
+            "-" => None,
+            "x" => Some(XOTokenWinState::X),
+            "o" => Some(XOTokenWinState::O),
+            "=" => Some(XOTokenWinState::Stale),
+            result => {
+                return Err(XONotationError::InvalidResultError {
+                    result: result.to_string(),
+                })
+            }
+        };
+        let rules = match fields.get(3) {
+            None => XORules::Normal,
+            Some(&"m") => XORules::Misere,
+            Some(rules) => {
+                return Err(XONotationError::InvalidRulesError {
+                    rules: rules.to_string(),
+                })
+            }
+        };
+        let board = board.set_rules(rules).set_win_state(win_state);
+
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+impl XO {
+    /// Format the game in single line [XO-FEN], see [`XOBoard::notation`]
+    ///
+    /// [XO-FEN]: notation/index.html
+    /// [`XOBoard::notation`]: struct.XOBoard.html#method.notation
+    pub fn notation(self) -> XONotation {
+        self.board().notation()
+    }
+}
+
+impl FromStr for XO {
+    type Err = XONotationError;
+
+    /// Parse [XO-FEN], see [`XOBoard::from_str`]
+    ///
+    /// [XO-FEN]: notation/index.html
+    /// [`XOBoard::from_str`]: struct.XOBoard.html#method.from_str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(XO::from_board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn round_trip() {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty(), XOBoard::empty().swap_turn()];
+        stack.extend(
+            stack
+                .clone()
+                .iter()
+                .map(|board| board.set_rules(XORules::Misere)),
+        );
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                let notation = board.notation().to_string();
+                assert_eq!(notation.parse(), Ok(board), "{}", notation);
+                stack.extend(board.children());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_error() {
+        use XONotationError::*;
+
+        let parse = |s: &str| s.parse::<XOBoard>().unwrap_err();
+        assert_eq!(parse("XX./.O./..."), FieldCountError { count: 1 });
+        assert_eq!(parse("XX./.O. o -"), RowCountError { count: 2 });
+        assert_eq!(
+            parse("XX./.O../... o -"),
+            RowLengthError { row: 1, length: 4 }
+        );
+        assert_eq!(parse("XX./.0./... o -"), InvalidCellError { cell: '0' });
+        assert_eq!(
+            parse("XX./.O./... X -"),
+            InvalidTurnError {
+                turn: "X".to_string()
+            }
+        );
+        assert_eq!(
+            parse("XXX/OO./... o ?"),
+            InvalidResultError {
+                result: "?".to_string()
+            }
+        );
+        assert_eq!(
+            parse("XXX/OO./... o -"),
+            BoardError {
+                source: XOBoardError::WinStateMismatchError
+            }
+        );
+        assert_eq!(
+            "XXX/OO./... o x".parse::<XO>().map(XO::win_state),
+            Ok(Some(XOTokenWinState::X))
+        );
+        assert_eq!(
+            parse("XXX/OO./... o x n"),
+            InvalidRulesError {
+                rules: "n".to_string()
+            }
+        );
+        assert_eq!(parse("XX./.O./... o - m m"), FieldCountError { count: 5 });
+    }
+
+    #[test]
+    fn misere() -> crate::XOResult {
+        use crate::xo_pos::XOPos;
+
+        let mut board = XOBoard::empty_with_rules(XORules::Misere);
+        for &index in [0, 3, 1, 4].iter() {
+            board = board.play(XOPos::index(index)?)?;
+        }
+        assert_eq!(board.notation().to_string(), "XX./OO./... x - m");
+        assert_eq!("XX./OO./... x - m".parse::<XOBoard>()?, board);
+
+        // X completed the top row, so O won
+        let board = board.play(XOPos::index(2)?)?;
+        assert_eq!(board.notation().to_string(), "XXX/OO./... o o m");
+        assert_eq!("XXX/OO./... o o m".parse::<XOBoard>()?, board);
+        assert_eq!(
+            "XXX/OO./... o o".parse::<XOBoard>(),
+            Err(XONotationError::BoardError {
+                source: XOBoardError::WinStateMismatchError
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use crate::board::{XOBoard, XORules};
+use crate::table::{ternary_rank, TERNARY_BOARDS};
+use crate::token::XOToken;
+use crate::validation::XOBoardError;
+
+use custom_error::custom_error;
+use std::convert::TryFrom;
+
+custom_error! {
+/// Error use when converting into base-3 rank or [`PackedXOBoard`]
+///
+/// [`PackedXOBoard`]: struct.PackedXOBoard.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOPackError
+    /// Error of a rank that isn't in [0, 3^9)
+    RankOutOfRangeError{rank: u16} = "invalid rank {rank}, expecting integer in [0, 19682]",
+    /// Error of packing a board that fail [`XOBoard::validate`]
+    BoardError{source: XOBoardError} = "attempt to pack an invalid board",
+    /// Error of packing a board that isn't played with [`XORules::Normal`]
+    ///
+    /// [`XORules::Normal`]: enum.XORules.html#variant.Normal
+    UnsupportedRulesError = "attempt to pack a board played with misère rules",
+}
+pub type XOPackResult<T = ()> = Result<T, XOPackError>;
+
+/// Number of distinct ranks (3^9)
+const RANK_COUNT: u16 = TERNARY_BOARDS as u16;
+
+impl XOBoard {
+    /// Base-3 rank of the cells, in [0, 3^9)
+    ///
+    /// Each cell is a digit (0 empty, 1 X, 2 O), cell index 0 being the least significant.
+    /// Turn and win state bits are ignored.
+    ///
+    /// # Error
+    /// Return [`XOBoardError::OverlapError`] if X and O are at the same cell
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let board = XOBoard::empty()
+    ///     .play(XOPos::index(0)?)?
+    ///     .play(XOPos::index(2)?)?;
+    /// assert_eq!(board.rank()?, 1 + 2 * 9);
+    /// assert_eq!(XOBoard::unrank(board.rank()?)?, board);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoardError::OverlapError`]: enum.XOBoardError.html#variant.OverlapError
+    pub fn rank(self) -> Result<u16, XOBoardError> {
+        let overlap = self.x_bit() & self.o_bit();
+        if overlap != 0 {
+            return Err(XOBoardError::OverlapError {
+                index: overlap.trailing_zeros(),
+            });
+        }
+        Ok(ternary_rank(self.x_bit(), self.o_bit()) as u16)
+    }
+
+    /// Construct board from base-3 rank of [`XOBoard::rank`]
+    ///
+    /// Turn is inferred assuming X started, and win state is [`XOBoard::evaluate_winner`].
+    /// The board isn't validated.
+    ///
+    /// # Error
+    /// Return [`XOPackError::RankOutOfRangeError`] if `rank` >= 3^9
+    ///
+    /// [`XOBoard::rank`]: struct.XOBoard.html#method.rank
+    /// [`XOBoard::evaluate_winner`]: struct.XOBoard.html#method.evaluate_winner
+    /// [`XOPackError::RankOutOfRangeError`]: enum.XOPackError.html#variant.RankOutOfRangeError
+    pub fn unrank(rank: u16) -> XOPackResult<XOBoard> {
+        unpack(rank, XOToken::X)
+    }
+}
+
+fn unpack(rank: u16, starter: XOToken) -> XOPackResult<XOBoard> {
+    if rank >= RANK_COUNT {
+        return Err(XOPackError::RankOutOfRangeError { rank });
+    }
+
+    let mut x_bit = 0;
+    let mut o_bit = 0;
+    let mut rest = rank;
+    for index in 0..9 {
+        match rest % 3 {
+            1 => x_bit |= 0b1 << index,
+            2 => o_bit |= 0b1 << index,
+            _ => {}
+        }
+        rest /= 3;
+    }
+
+    let board = XOBoard::new(x_bit | o_bit << 9);
+    let turn = if x_bit.count_ones() == o_bit.count_ones() {
+        starter
+    } else {
+        starter.opposite_token()
+    };
+    let board = if turn == XOToken::X {
+        board
+    } else {
+        board.swap_turn()
+    };
+    Ok(board.set_win_state(board.evaluate_winner()))
+}
+
+/// Board packed into 16 bit, half the size of [`XOBoard`]
+///
+/// Only the cells are stored (as [`XOBoard::rank`]) along with which player started,
+/// turn and win state are derived on demand.
+/// So only boards passing [`XOBoard::validate`] and played with [`XORules::Normal`] can be packed,
+/// which convert losslessly.
+///
+/// The rank take 15 bit (3^9 = 19683 values) and the starter the last bit. Keeping the rules too
+/// would need 4 × 3^9 = 78732 values, more than `u16` can hold, so boards played with
+/// [`XORules::Misere`] are rejected with [`XOPackError::UnsupportedRulesError`].
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::{PackedXOBoard, XOBoard, XOPos, XOToken};
+/// use std::convert::TryFrom;
+///
+/// let board = XOBoard::empty()
+///     .play(XOPos::index(4)?)?
+///     .play(XOPos::index(0)?)?;
+/// let packed = PackedXOBoard::try_from(board)?;
+/// assert_eq!(std::mem::size_of_val(&packed), 2);
+/// assert_eq!(packed.turn(), XOToken::X);
+/// assert_eq!(XOBoard::from(packed), board);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`XOBoard`]: struct.XOBoard.html
+/// [`XOBoard::rank`]: struct.XOBoard.html#method.rank
+/// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+/// [`XORules::Normal`]: enum.XORules.html#variant.Normal
+/// [`XORules::Misere`]: enum.XORules.html#variant.Misere
+/// [`XOPackError::UnsupportedRulesError`]: enum.XOPackError.html#variant.UnsupportedRulesError
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PackedXOBoard(u16);
+
+const O_STARTED_BIT: u16 = 0b1 << 15;
+
+impl PackedXOBoard {
+    /// Retrieve the raw bits, bit 0-14 is the rank and bit 15 is set if O started
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Base-3 rank of the cells, see [`XOBoard::rank`]
+    ///
+    /// [`XOBoard::rank`]: struct.XOBoard.html#method.rank
+    pub fn rank(self) -> u16 {
+        self.0 & !O_STARTED_BIT
+    }
+
+    /// Player who made the first move
+    pub fn starter(self) -> XOToken {
+        if self.0 & O_STARTED_BIT == 0 {
+            XOToken::X
+        } else {
+            XOToken::O
+        }
+    }
+
+    /// Unpack into [`XOBoard`]
+    ///
+    /// [`XOBoard`]: struct.XOBoard.html
+    pub fn unpack(self) -> XOBoard {
+        unpack(self.rank(), self.starter()).expect("packed board is always valid")
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(self) -> XOToken {
+        self.unpack().turn()
+    }
+
+    /// Get information about who (if any) won the game, see [`XOBoard::win_state`]
+    ///
+    /// [`XOBoard::win_state`]: struct.XOBoard.html#method.win_state
+    pub fn win_state(self) -> Option<crate::XOTokenWinState> {
+        self.unpack().evaluate_winner()
+    }
+}
+
+impl TryFrom<XOBoard> for PackedXOBoard {
+    type Error = XOPackError;
+
+    fn try_from(board: XOBoard) -> XOPackResult<Self> {
+        board.validate()?;
+        // rank and starter already use all 16 bit, see PackedXOBoard
+        if board.rules() != XORules::Normal {
+            return Err(XOPackError::UnsupportedRulesError);
+        }
+
+        let x_count = board.x_bit().count_ones();
+        let o_count = board.o_bit().count_ones();
+        let o_started = if x_count == o_count {
+            board.turn() == XOToken::O
+        } else {
+            o_count > x_count
+        };
+
+        let rank = board.rank()?;
+        Ok(PackedXOBoard(if o_started {
+            rank | O_STARTED_BIT
+        } else {
+            rank
+        }))
+    }
+}
+
+impl TryFrom<u16> for PackedXOBoard {
+    type Error = XOPackError;
+
+    /// Check that `bits` (see [`PackedXOBoard::bits`]) unpack to a valid board
+    ///
+    /// [`PackedXOBoard::bits`]: struct.PackedXOBoard.html#method.bits
+    fn try_from(bits: u16) -> XOPackResult<Self> {
+        let packed = PackedXOBoard(bits);
+        unpack(packed.rank(), packed.starter())?.validate()?;
+        Ok(packed)
+    }
+}
+
+impl From<PackedXOBoard> for XOBoard {
+    fn from(packed: PackedXOBoard) -> Self {
+        packed.unpack()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PackedXOBoard {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u16::deserialize(deserializer)?;
+        PackedXOBoard::try_from(bits).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn lossless() {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty(), XOBoard::empty().swap_turn()];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                let packed = PackedXOBoard::try_from(board).unwrap();
+                assert_eq!(XOBoard::from(packed), board);
+                assert_eq!(packed.win_state(), board.win_state());
+                stack.extend(board.children());
+            }
+        }
+
+        let packable = (0..=u16::MAX)
+            .filter_map(|bits| PackedXOBoard::try_from(bits).ok())
+            .count();
+        assert_eq!(packable, seen.len());
+    }
+
+    #[test]
+    fn reject_misere() {
+        let mut seen = HashSet::new();
+        let empty = XOBoard::empty_with_rules(XORules::Misere);
+        let mut stack = vec![empty, empty.swap_turn()];
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                assert_eq!(
+                    PackedXOBoard::try_from(board),
+                    Err(XOPackError::UnsupportedRulesError)
+                );
+                // the cells still round-trip through the rank
+                let normal = XOBoard::unrank(board.rank().unwrap()).unwrap();
+                assert_eq!(normal.x_bit(), board.x_bit());
+                assert_eq!(normal.o_bit(), board.o_bit());
+                stack.extend(board.children());
+            }
+        }
+        assert!((4 * RANK_COUNT as u32) > u32::from(u16::MAX));
+    }
+
+    #[test]
+    fn rank_range() {
+        assert_eq!(XOBoard::unrank(0), Ok(XOBoard::empty()));
+        let full_o = XOBoard::unrank(RANK_COUNT - 1).unwrap();
+        assert_eq!(full_o.o_bit(), 0b111_111_111);
+        assert_eq!(full_o.rank(), Ok(RANK_COUNT - 1));
+        assert_eq!(
+            XOBoard::unrank(RANK_COUNT),
+            Err(XOPackError::RankOutOfRangeError { rank: RANK_COUNT })
+        );
+        assert_eq!(
+            XOBoard::new(0b1 | 0b1 << 9).rank(),
+            Err(XOBoardError::OverlapError { index: 0 })
+        );
+    }
+}
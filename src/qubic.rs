@@ -0,0 +1,401 @@
+//! Qubic, 3D xo on a 4x4x4 cube where 4 in a row (along any of the 76 lines) win
+//!
+//! Each player's cells are a `u64` with cell (x, y, z) at bit `x + 4 * y + 16 * z`.
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::qubic::{QubicBoard, QubicPos};
+//! use bitboard_xo::XOTokenWinState;
+//!
+//! // X take the space diagonal while O play on the bottom layer
+//! let mut board = QubicBoard::empty();
+//! for i in 0..4 {
+//!     board = board.play(QubicPos::new(i, i, i)?)?;
+//!     if i < 3 {
+//!         board = board.play(QubicPos::new(i + 1, 0, 0)?)?;
+//!     }
+//! }
+//! assert_eq!(board.win_state(), Some(XOTokenWinState::X));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::{XOGameError, XOGameResult};
+use crate::token::{XOToken, XOTokenWinState};
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::iter;
+
+custom_error! {
+/// Error use when attempting to constructing invalid [`QubicPos`]
+///
+/// [`QubicPos`]: struct.QubicPos.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub QubicPosError
+    InvalidQubicIndex {index: u32} = "invalid qubic's index: {index}, expecting integer in [0, 63]",
+    InvalidQubicCoordinate {x: u32, y: u32, z: u32} =
+        "invalid qubic's coordinate: ({x}, {y}, {z}), expecting each in [0, 3]",
+}
+
+/// Position in the 4x4x4 cube
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QubicPos {
+    index: u32,
+}
+
+impl QubicPos {
+    /// Create [`QubicPos`] from (x, y, z) coordinate
+    ///
+    /// # Error
+    /// Return [`QubicPosError::InvalidQubicCoordinate`] if any coordinate >= 4
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    /// [`QubicPosError::InvalidQubicCoordinate`]: enum.QubicPosError.html#variant.InvalidQubicCoordinate
+    pub fn new(x: u32, y: u32, z: u32) -> Result<Self, QubicPosError> {
+        if x >= 4 || y >= 4 || z >= 4 {
+            return Err(QubicPosError::InvalidQubicCoordinate { x, y, z });
+        }
+        Ok(QubicPos {
+            index: x + 4 * y + 16 * z,
+        })
+    }
+
+    /// Create [`QubicPos`] from index `x + 4 * y + 16 * z`
+    ///
+    /// # Error
+    /// Return [`QubicPosError::InvalidQubicIndex`] if `index` >= 64
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    /// [`QubicPosError::InvalidQubicIndex`]: enum.QubicPosError.html#variant.InvalidQubicIndex
+    pub fn index(index: u32) -> Result<Self, QubicPosError> {
+        if index >= 64 {
+            return Err(QubicPosError::InvalidQubicIndex { index });
+        }
+        Ok(QubicPos { index })
+    }
+
+    /// Retrieve index `x + 4 * y + 16 * z` of this [`QubicPos`], guaranteed to be in [0, 64)
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    pub fn as_index(self) -> u32 {
+        self.index
+    }
+
+    /// Retrieve x coordinate of this [`QubicPos`], guaranteed to be in [0, 4)
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    pub fn x(self) -> u32 {
+        self.index % 4
+    }
+
+    /// Retrieve y coordinate of this [`QubicPos`], guaranteed to be in [0, 4)
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    pub fn y(self) -> u32 {
+        self.index / 4 % 4
+    }
+
+    /// Retrieve z coordinate (layer) of this [`QubicPos`], guaranteed to be in [0, 4)
+    ///
+    /// [`QubicPos`]: struct.QubicPos.html
+    pub fn z(self) -> u32 {
+        self.index / 16
+    }
+
+    fn bit(self) -> u64 {
+        0b1 << self.index
+    }
+}
+
+/// The 13 line directions as (dx, dy, dz), each with a positive step `dx + 4 * dy + 16 * dz`
+const DIRECTIONS: [(i32, i32, i32); 13] = [
+    (1, 0, 0),
+    (0, 1, 0),
+    (0, 0, 1),
+    (1, 1, 0),
+    (-1, 1, 0),
+    (1, 0, 1),
+    (-1, 0, 1),
+    (0, 1, 1),
+    (0, -1, 1),
+    (1, 1, 1),
+    (-1, 1, 1),
+    (1, -1, 1),
+    (-1, -1, 1),
+];
+
+/// Number of winning lines
+pub const QUBIC_LINES: usize = 76;
+
+/// Step and mask of cells starting a line, for every direction
+static LINE_STARTS: [(u32, u64); 13] = line_starts();
+
+const fn line_starts() -> [(u32, u64); 13] {
+    let mut starts = [(0, 0); 13];
+    let mut direction = 0;
+    while direction < DIRECTIONS.len() {
+        let (dx, dy, dz) = DIRECTIONS[direction];
+        let mut mask = 0;
+        let mut index = 0;
+        while index < 64 {
+            let (x, y, z) = (index % 4, index / 4 % 4, index / 16);
+            let (end_x, end_y, end_z) = (x + 3 * dx, y + 3 * dy, z + 3 * dz);
+            if 0 <= end_x && end_x < 4 && 0 <= end_y && end_y < 4 && 0 <= end_z && end_z < 4 {
+                mask |= 0b1 << index;
+            }
+            index += 1;
+        }
+        starts[direction] = ((dx + 4 * dy + 16 * dz) as u32, mask);
+        direction += 1;
+    }
+    starts
+}
+
+/// Check if `bits` contain 4 in a row, by shift-and along every direction
+fn has_line(bits: u64) -> bool {
+    LINE_STARTS.iter().any(|&(step, starts)| {
+        bits & bits >> step & bits >> (2 * step) & bits >> (3 * step) & starts != 0
+    })
+}
+
+/// Every winning line as a 64 bit mask
+pub fn lines() -> impl Iterator<Item = u64> {
+    LINE_STARTS.iter().flat_map(|&(step, starts)| {
+        (0..64)
+            .filter(move |index| starts >> index & 0b1 == 1)
+            .map(move |index| (0b1 | 0b1 << step | 0b1 << (2 * step) | 0b1 << (3 * step)) << index)
+    })
+}
+
+/// Board of Qubic (4x4x4 xo), X always start
+///
+/// Like [`XOBoard`] every method return a new board.
+///
+/// [`XOBoard`]: ../struct.XOBoard.html
+#[must_use = "QubicBoard's method doesn't mutate but return a new board"]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QubicBoard {
+    x_bit: u64,
+    o_bit: u64,
+    turn: XOToken,
+    win_state: Option<XOTokenWinState>,
+}
+
+impl QubicBoard {
+    /// Construct an empty board with X to play
+    pub fn empty() -> Self {
+        QubicBoard {
+            x_bit: 0,
+            o_bit: 0,
+            turn: XOToken::X,
+            win_state: None,
+        }
+    }
+
+    /// Retrieve cells occupied by X, cell (x, y, z) at bit `x + 4 * y + 16 * z`
+    pub fn x_bit(self) -> u64 {
+        self.x_bit
+    }
+
+    /// Retrieve cells occupied by O, cell (x, y, z) at bit `x + 4 * y + 16 * z`
+    pub fn o_bit(self) -> u64 {
+        self.o_bit
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(self) -> XOToken {
+        self.turn
+    }
+
+    /// Get information about who (if any) won the game
+    pub fn win_state(self) -> Option<XOTokenWinState> {
+        self.win_state
+    }
+
+    /// Check if the game has ended, either by a win or a full cube
+    pub fn game_ended(self) -> bool {
+        self.win_state.is_some()
+    }
+
+    /// Get token at `pos`, `None` if empty
+    pub fn get(self, pos: QubicPos) -> Option<XOToken> {
+        if self.x_bit & pos.bit() != 0 {
+            Some(XOToken::X)
+        } else if self.o_bit & pos.bit() != 0 {
+            Some(XOToken::O)
+        } else {
+            None
+        }
+    }
+
+    /// Play at `pos` for the current player, the same rule as [`XOBoard::play`]
+    ///
+    /// # Error
+    /// Return [`XOGameError::GameEndedError`] if the game has ended,
+    /// or [`XOGameError::AlreadyPlayedError`] (with [`QubicPos::as_index`]) if `pos` isn't empty
+    ///
+    /// [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+    /// [`XOGameError::GameEndedError`]: ../enum.XOGameError.html#variant.GameEndedError
+    /// [`XOGameError::AlreadyPlayedError`]: ../enum.XOGameError.html#variant.AlreadyPlayedError
+    /// [`QubicPos::as_index`]: struct.QubicPos.html#method.as_index
+    pub fn play(self, pos: QubicPos) -> XOGameResult<Self> {
+        if self.game_ended() {
+            return Err(XOGameError::GameEndedError);
+        }
+        if (self.x_bit | self.o_bit) & pos.bit() != 0 {
+            return Err(XOGameError::AlreadyPlayedError {
+                index: pos.as_index(),
+            });
+        }
+
+        let mut board = self;
+        let mover_bit = match self.turn {
+            XOToken::X => {
+                board.x_bit |= pos.bit();
+                board.x_bit
+            }
+            XOToken::O => {
+                board.o_bit |= pos.bit();
+                board.o_bit
+            }
+        };
+        // the game was ongoing, so only a line of the player who just played can appear
+        board.win_state = if has_line(mover_bit) {
+            Some(XOTokenWinState::from(self.turn))
+        } else if board.x_bit | board.o_bit == u64::MAX {
+            Some(XOTokenWinState::Stale)
+        } else {
+            None
+        };
+        board.turn = self.turn.opposite_token();
+        Ok(board)
+    }
+
+    /// Evaluate win state from the cells
+    pub fn evaluate_winner(self) -> Option<XOTokenWinState> {
+        if has_line(self.x_bit) {
+            Some(XOTokenWinState::X)
+        } else if has_line(self.o_bit) {
+            Some(XOTokenWinState::O)
+        } else if self.x_bit | self.o_bit == u64::MAX {
+            Some(XOTokenWinState::Stale)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate through positions that can be played, empty once the game has ended
+    pub fn legal_moves(self) -> impl Iterator<Item = QubicPos> {
+        let mut free = if self.game_ended() {
+            0
+        } else {
+            !(self.x_bit | self.o_bit)
+        };
+        iter::from_fn(move || {
+            if free == 0 {
+                return None;
+            }
+
+            let index = free.trailing_zeros();
+            free &= free - 1;
+            Some(QubicPos { index })
+        })
+    }
+}
+
+impl Default for QubicBoard {
+    fn default() -> Self {
+        QubicBoard::empty()
+    }
+}
+
+impl Display for QubicBoard {
+    /// Show the 4 layers from z = 0 to 3 separated by an empty line,
+    /// each with row y = 0 at the top and column x = 0 at the left
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        if let Some(winner) = self.win_state() {
+            match winner {
+                XOTokenWinState::Stale => writeln!(f, "Game Ended In Stale Mate")?,
+                XOTokenWinState::X | XOTokenWinState::O => {
+                    writeln!(f, "Game Ended: {}'s winner", winner)?
+                }
+            }
+        } else {
+            writeln!(f, "{}'s Turn", self.turn())?;
+        }
+
+        for z in 0..4 {
+            if z != 0 {
+                f.write_char('\n')?;
+            }
+            for y in 0..4 {
+                for x in 0..4 {
+                    match self.get(QubicPos {
+                        index: x + 4 * y + 16 * z,
+                    }) {
+                        Some(XOToken::X) => f.write_char('X')?,
+                        Some(XOToken::O) => f.write_char('O')?,
+                        None => f.write_char('.')?,
+                    }
+                }
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+    use std::collections::HashSet;
+
+    #[test]
+    fn seventy_six_lines() {
+        let lines: HashSet<u64> = lines().collect();
+        assert_eq!(lines.len(), QUBIC_LINES);
+        assert!(lines.iter().all(|line| line.count_ones() == 4));
+        for &line in lines.iter() {
+            assert!(has_line(line));
+            // removing any cell break the line
+            for index in 0..64 {
+                if line >> index & 0b1 == 1 {
+                    assert!(!has_line(line & !(0b1 << index)));
+                }
+            }
+        }
+        // the 8 corners and 8 center cells are on 7 lines, every other cell on 4 lines
+        let lines_through = |index: u32| {
+            lines
+                .iter()
+                .filter(|&&line| line >> index & 0b1 == 1)
+                .count()
+        };
+        let seven: Vec<u32> = (0..64).filter(|&index| lines_through(index) == 7).collect();
+        assert_eq!(seven.len(), 16);
+        assert!((0..64).all(|index| seven.contains(&index) || lines_through(index) == 4));
+        assert!(seven.contains(&0) && seven.contains(&21) && seven.contains(&63));
+    }
+
+    #[test]
+    fn play() -> XOResult {
+        let pos = QubicPos::new(1, 2, 3)?;
+        assert_eq!((pos.x(), pos.y(), pos.z()), (1, 2, 3));
+        let board = QubicBoard::empty().play(pos)?;
+        assert_eq!(board.get(pos), Some(XOToken::X));
+        assert_eq!(
+            board.play(pos),
+            Err(XOGameError::AlreadyPlayedError { index: 57 })
+        );
+        assert_eq!(board.legal_moves().count(), 63);
+        assert_eq!(
+            QubicPos::new(4, 0, 0),
+            Err(QubicPosError::InvalidQubicCoordinate { x: 4, y: 0, z: 0 })
+        );
+        Ok(())
+    }
+}
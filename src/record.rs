@@ -0,0 +1,400 @@
+//! Game record with metadata, stored in a PGN-like text format
+//!
+//! A record start with tag pairs, one per line, followed by a blank line and the moves.
+//! Moves use [`XOPos`]'s coordinate (`a1` to `c3`), numbered every two plies
+//! like PGN, and end with the result (`x`, `o`, `=` for stalemate or `*` if unknown).
+//! The starting position is only written (as [XO-FEN]) when it isn't the empty board,
+//! so a game played under [`XORules::Misere`] always keep its rules in the `Setup` tag.
+//!
+//! ```text
+//! [Event "Club championship"]
+//! [Date "2026.10.17"]
+//! [X "Alice"]
+//! [O "Bob"]
+//! [Result "="]
+//!
+//! 1. b2 a1 2. c1 a3 3. a2 c2 4. b1 b3 5. c3 =
+//! ```
+//!
+//! [`XOPos`]: ../xo_pos/struct.XOPos.html
+//! [`XORules::Misere`]: ../enum.XORules.html#variant.Misere
+//! [XO-FEN]: ../notation/index.html
+
+use crate::board::{XOBoard, XOGameError};
+use crate::history::XOHistory;
+use crate::notation::XONotationError;
+use crate::token::XOTokenWinState;
+use crate::xo_pos::{XOCoordinateError, XOPos};
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+custom_error! {
+/// Error use when parsing or replaying invalid game record
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XORecordError
+    /// Error of a tag line that isn't `[Name "value"]`
+    InvalidTagError{line: String} = "invalid tag pair: {line}",
+    /// Error of a move that isn't a coordinate
+    InvalidMoveError{source: XOCoordinateError} = "invalid move",
+    /// Error of a result that isn't `x`, `o`, `=` or `*`
+    InvalidResultError{result: String} =
+        "invalid result '{result}', expecting one of 'x', 'o', '=', '*'",
+    /// Error of a game termination marker that disagree with the `Result` tag
+    ResultTagMismatchError{result: String} =
+        "game termination marker '{result}' disagree with the Result tag",
+    /// Error of a starting position that isn't valid XO-FEN
+    SetupError{source: XONotationError} = "invalid starting position",
+    /// Error of an illegal move found when replaying, `ply` count from 1
+    IllegalMoveError{ply: usize, source: XOGameError} = "illegal move at ply {ply}",
+    /// Error of a result that disagree with the replayed game
+    ResultMismatchError = "recorded result disagree with the replayed game",
+}
+pub type XORecordResult<T = ()> = Result<T, XORecordError>;
+
+/// Record of a xo game, holding metadata and every move
+///
+/// ```rust
+/// # use bitboard_xo::XOResult;
+/// # fn main() -> XOResult {
+/// use bitboard_xo::record::XORecord;
+/// use bitboard_xo::{XOPos, XOTokenWinState};
+///
+/// let mut record = XORecord::new();
+/// record.x_player = Some("Alice".to_string());
+/// record.result = Some(XOTokenWinState::X);
+/// for coordinate in ["a1", "a2", "b1", "b2", "c1"].iter() {
+///     record.moves.push(coordinate.parse()?);
+/// }
+///
+/// let text = record.to_string();
+/// let parsed: XORecord = text.parse()?;
+/// assert_eq!(parsed, record);
+/// assert_eq!(parsed.replay()?.win_state(), Some(XOTokenWinState::X));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct XORecord {
+    pub event: Option<String>,
+    pub date: Option<String>,
+    pub x_player: Option<String>,
+    pub o_player: Option<String>,
+    /// Recorded result, `None` if unknown or game didn't end
+    pub result: Option<XOTokenWinState>,
+    /// Other tag pairs, kept in order
+    pub other_tags: Vec<(String, String)>,
+    pub start: XOBoard,
+    pub moves: Vec<XOPos>,
+}
+
+impl XORecord {
+    /// Construct a record starting from the empty board, without metadata or move
+    pub fn new() -> Self {
+        XORecord::default()
+    }
+
+    /// Replay the moves from the starting board with [`XOBoard::play`]
+    /// and return the final board
+    ///
+    /// # Error
+    /// Return [`XORecordError::IllegalMoveError`] on the first illegal move, or
+    /// [`XORecordError::ResultMismatchError`] if [`XORecord::result`] is known
+    /// but disagree with the final board
+    ///
+    /// [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+    /// [`XORecordError::IllegalMoveError`]: enum.XORecordError.html#variant.IllegalMoveError
+    /// [`XORecordError::ResultMismatchError`]: enum.XORecordError.html#variant.ResultMismatchError
+    /// [`XORecord::result`]: struct.XORecord.html#structfield.result
+    pub fn replay(&self) -> XORecordResult<XOBoard> {
+        let mut board = self.start;
+        for (ply, &pos) in self.moves.iter().enumerate() {
+            board = board
+                .play(pos)
+                .map_err(|source| XORecordError::IllegalMoveError {
+                    ply: ply + 1,
+                    source,
+                })?;
+        }
+
+        if self.result.is_some() && self.result != board.win_state() {
+            return Err(XORecordError::ResultMismatchError);
+        }
+        Ok(board)
+    }
+
+    /// Replay the moves into [`XOHistory`], see [`XORecord::replay`]
+    ///
+    /// [`XOHistory`]: ../struct.XOHistory.html
+    /// [`XORecord::replay`]: struct.XORecord.html#method.replay
+    pub fn to_history(&self) -> XORecordResult<XOHistory> {
+        let board = self.replay()?;
+        let mut history = XOHistory::from_board(self.start);
+        for &pos in self.moves.iter() {
+            history.play(pos).expect("moves has already been replayed");
+        }
+        debug_assert_eq!(history.board(), board);
+        Ok(history)
+    }
+}
+
+impl From<&XOHistory> for XORecord {
+    /// Record moves played in `history` (without moves that could be redone)
+    fn from(history: &XOHistory) -> Self {
+        XORecord {
+            result: history.win_state(),
+            start: history.start_board(),
+            moves: history.history().to_vec(),
+            ..XORecord::default()
+        }
+    }
+}
+
+fn result_token(result: Option<XOTokenWinState>) -> &'static str {
+    match result {
+        None => "*",
+        Some(XOTokenWinState::X) => "x",
+        Some(XOTokenWinState::O) => "o",
+        Some(XOTokenWinState::Stale) => "=",
+    }
+}
+
+fn parse_result(result: &str) -> XORecordResult<Option<XOTokenWinState>> {
+    match result {
+        "*" => Ok(None),
+        "x" => Ok(Some(XOTokenWinState::X)),
+        "o" => Ok(Some(XOTokenWinState::O)),
+        "=" => Ok(Some(XOTokenWinState::Stale)),
+        _ => Err(XORecordError::InvalidResultError {
+            result: result.to_string(),
+        }),
+    }
+}
+
+fn write_tag(f: &mut Formatter, name: &str, value: &str) -> Result<(), fmt::Error> {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{} \"{}\"]", name, escaped)
+}
+
+/// Parse `[Name "value"]` into name and unescaped value
+fn parse_tag(line: &str) -> XORecordResult<(String, String)> {
+    let invalid = || XORecordError::InvalidTagError {
+        line: line.to_string(),
+    };
+
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .ok_or_else(invalid)?;
+    let quote = inner.find('"').ok_or_else(invalid)?;
+    let name = inner[..quote].trim();
+    let quoted = inner[quote..].trim_end();
+    if name.is_empty() || quoted.len() < 2 || !quoted.ends_with('"') {
+        return Err(invalid());
+    }
+
+    let mut value = String::new();
+    let mut chars = quoted[1..quoted.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next().ok_or_else(invalid)?),
+            '"' => return Err(invalid()),
+            c => value.push(c),
+        }
+    }
+    Ok((name.to_string(), value))
+}
+
+impl Display for XORecord {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        let tags = [
+            ("Event", &self.event),
+            ("Date", &self.date),
+            ("X", &self.x_player),
+            ("O", &self.o_player),
+        ];
+        for (name, value) in tags.iter() {
+            if let Some(value) = value {
+                write_tag(f, name, value)?;
+            }
+        }
+        write_tag(f, "Result", result_token(self.result))?;
+        if self.start != XOBoard::empty() {
+            write_tag(f, "Setup", &self.start.notation().to_string())?;
+        }
+        for (name, value) in self.other_tags.iter() {
+            write_tag(f, name, value)?;
+        }
+        writeln!(f)?;
+
+        for (ply, pos) in self.moves.iter().enumerate() {
+            if ply % 2 == 0 {
+                write!(f, "{}. ", ply / 2 + 1)?;
+            }
+            write!(f, "{} ", pos)?;
+        }
+        writeln!(f, "{}", result_token(self.result))
+    }
+}
+
+impl FromStr for XORecord {
+    type Err = XORecordError;
+
+    /// Parse record formatted by [`XORecord`]'s `Display`
+    ///
+    /// Parsing doesn't replay the moves, use [`XORecord::replay`] to check them
+    ///
+    /// [`XORecord`]: struct.XORecord.html
+    /// [`XORecord::replay`]: struct.XORecord.html#method.replay
+    fn from_str(s: &str) -> XORecordResult<XORecord> {
+        let mut record = XORecord::new();
+        let mut lines = s.lines().map(str::trim).peekable();
+        let mut has_result_tag = false;
+
+        while let Some(line) = lines.next_if(|line| line.is_empty() || line.starts_with('[')) {
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = parse_tag(line)?;
+            match name.as_str() {
+                "Event" => record.event = Some(value),
+                "Date" => record.date = Some(value),
+                "X" => record.x_player = Some(value),
+                "O" => record.o_player = Some(value),
+                "Result" => {
+                    record.result = parse_result(&value)?;
+                    has_result_tag = true;
+                }
+                "Setup" => record.start = value.parse()?,
+                _ => record.other_tags.push((name, value)),
+            }
+        }
+
+        let mut tokens = lines.flat_map(str::split_whitespace).peekable();
+        while let Some(token) = tokens.next() {
+            // move number
+            if token.ends_with('.') && token[..token.len() - 1].bytes().all(|b| b.is_ascii_digit())
+            {
+                continue;
+            }
+            // game termination marker, must agree with the result tag if any
+            if tokens.peek().is_none() && ["x", "o", "=", "*"].contains(&token) {
+                let result = parse_result(token)?;
+                if has_result_tag && result != record.result {
+                    return Err(XORecordError::ResultTagMismatchError {
+                        result: token.to_string(),
+                    });
+                }
+                record.result = result;
+                break;
+            }
+            record.moves.push(token.parse()?);
+        }
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn round_trip() -> XOResult {
+        let mut record = XORecord::new();
+        record.event = Some("Quote \"test\" \\ backslash".to_string());
+        record.o_player = Some("Bob".to_string());
+        record
+            .other_tags
+            .push(("Round".to_string(), "3".to_string()));
+        record.start = "X../.O./... x -".parse()?;
+        record.moves = vec!["c3".parse()?, "a3".parse()?];
+
+        let text = record.to_string();
+        assert_eq!(
+            text,
+            "[Event \"Quote \\\"test\\\" \\\\ backslash\"]\n\
+             [O \"Bob\"]\n\
+             [Result \"*\"]\n\
+             [Setup \"X../.O./... x -\"]\n\
+             [Round \"3\"]\n\
+             \n\
+             1. c3 a3 *\n"
+        );
+        assert_eq!(text.parse(), Ok(record));
+        Ok(())
+    }
+
+    #[test]
+    fn report_first_illegal_move() -> XOResult {
+        let record: XORecord = "[Result \"*\"]\n\n1. b2 a1 2. b2 a1 *".parse()?;
+        assert_eq!(
+            record.replay(),
+            Err(XORecordError::IllegalMoveError {
+                ply: 3,
+                source: XOGameError::AlreadyPlayedError { index: 4 }
+            })
+        );
+
+        let record: XORecord = "[Result \"o\"]\n\n1. a1 b1 2. a2 b2 3. a3 o".parse()?;
+        assert_eq!(record.replay(), Err(XORecordError::ResultMismatchError));
+
+        assert_eq!(
+            "1. a4 *".parse::<XORecord>(),
+            Err(XORecordError::InvalidMoveError {
+                source: XOCoordinateError {
+                    coordinate: "a4".to_string()
+                }
+            })
+        );
+        assert!(matches!(
+            "[Event unquoted]".parse::<XORecord>(),
+            Err(XORecordError::InvalidTagError { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn result_tag_and_marker_disagree() -> XOResult {
+        let record: XORecord = "[Result \"x\"]\n\n1. a1 b1 2. a2 b2 3. a3 x".parse()?;
+        assert_eq!(record.result, Some(XOTokenWinState::X));
+        assert_eq!(
+            "[Result \"x\"]\n\n1. a1 b1 2. a2 b2 3. a3 o".parse::<XORecord>(),
+            Err(XORecordError::ResultTagMismatchError {
+                result: "o".to_string()
+            })
+        );
+        assert_eq!(
+            "[Result \"=\"]\n\n1. a1 *".parse::<XORecord>(),
+            Err(XORecordError::ResultTagMismatchError {
+                result: "*".to_string()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn misere_round_trip() -> XOResult {
+        use crate::board::XORules;
+
+        let mut record = XORecord::new();
+        record.start = XOBoard::empty_with_rules(XORules::Misere);
+        record.result = Some(XOTokenWinState::O);
+        record.moves = ["a1", "a2", "b1", "b2", "c1"]
+            .iter()
+            .map(|coordinate| coordinate.parse())
+            .collect::<Result<_, _>>()?;
+
+        let text = record.to_string();
+        assert!(text.contains("[Setup \".../.../... x - m\"]\n"), "{}", text);
+        let parsed: XORecord = text.parse()?;
+        assert_eq!(parsed, record);
+        // X completed the top row, so O won under misère rules
+        assert_eq!(parsed.replay()?.win_state(), Some(XOTokenWinState::O));
+        assert_eq!(parsed.replay()?.rules(), XORules::Misere);
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! `Serialize` and `Deserialize` for types that can't simply be derived
+//!
+//! Human-readable formats (such as JSON) get a cell grid and row/col,
+//! other formats get the raw bits and index.
+//! Deserialization validate its input, so it never construct an impossible board.
+
+use crate::board::{XOBoard, XORules};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::xo_pos::XOPos;
+use crate::XO;
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RowCol {
+    row: u32,
+    col: u32,
+}
+
+impl Serialize for XOPos {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            RowCol {
+                row: self.row(),
+                col: self.col(),
+            }
+            .serialize(serializer)
+        } else {
+            serializer.serialize_u8(self.as_index() as u8)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for XOPos {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let RowCol { row, col } = RowCol::deserialize(deserializer)?;
+            XOPos::row_col(row, col).map_err(de::Error::custom)
+        } else {
+            let index = u8::deserialize(deserializer)?;
+            XOPos::index(u32::from(index)).map_err(de::Error::custom)
+        }
+    }
+}
+
+/// Human-readable form of [`XOBoard`], rows of `X`, `O` and `.` from top to bottom
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardGrid {
+    cells: [String; 3],
+    turn: XOToken,
+    win_state: Option<XOTokenWinState>,
+    rules: XORules,
+}
+
+impl Serialize for XOBoard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u32(self.bits());
+        }
+
+        let mut cells: [String; 3] = Default::default();
+        for (index, maybe_token) in self.iter().enumerate() {
+            cells[index / 3].push(match maybe_token {
+                Some(XOToken::X) => 'X',
+                Some(XOToken::O) => 'O',
+                None => '.',
+            });
+        }
+        BoardGrid {
+            cells,
+            turn: self.turn(),
+            win_state: self.win_state(),
+            rules: self.rules(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for XOBoard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let bits = u32::deserialize(deserializer)?;
+            return XOBoard::try_from(bits).map_err(de::Error::custom);
+        }
+
+        let grid = BoardGrid::deserialize(deserializer)?;
+        let mut cells = [None; 9];
+        for (row, row_cells) in grid.cells.iter().enumerate() {
+            if row_cells.chars().count() != 3 {
+                return Err(de::Error::invalid_length(
+                    row_cells.chars().count(),
+                    &"3 cells in a row",
+                ));
+            }
+            for (col, cell) in row_cells.chars().enumerate() {
+                cells[3 * row + col] = match cell {
+                    'X' => Some(XOToken::X),
+                    'O' => Some(XOToken::O),
+                    '.' => None,
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Char(cell),
+                            &"one of 'X', 'O', '.'",
+                        ))
+                    }
+                };
+            }
+        }
+
+        let board = XOBoard::from_maybe_token_array(cells);
+        let board = if board.turn() == grid.turn {
+            board
+        } else {
+            board.swap_turn()
+        };
+        let board = board.set_rules(grid.rules).set_win_state(grid.win_state);
+        board.validate().map_err(de::Error::custom)?;
+        Ok(board)
+    }
+}
+
+impl Serialize for XO {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.board().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for XO {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        XOBoard::deserialize(deserializer).map(XO::from_board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::XOBoardError;
+    use crate::XOResult;
+
+    #[test]
+    fn human_readable() -> XOResult {
+        let game: XO = "XX./.O./... o -".parse()?;
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(
+            json,
+            r#"{"cells":["XX.",".O.","..."],"turn":"O","win_state":null,"rules":"Normal"}"#
+        );
+        assert_eq!(serde_json::from_str::<XO>(&json).unwrap(), game);
+
+        let pos = XOPos::row_col(1, 2)?;
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(json, r#"{"row":1,"col":2}"#);
+        assert_eq!(serde_json::from_str::<XOPos>(&json).unwrap(), pos);
+        assert!(serde_json::from_str::<XOPos>(r#"{"row":3,"col":2}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn compact() -> XOResult {
+        let board: XOBoard = "XXX/OO./... o x".parse()?;
+        let bytes = bincode::serialize(&board).unwrap();
+        assert_eq!(bytes, board.bits().to_le_bytes());
+        assert_eq!(bincode::deserialize::<XOBoard>(&bytes).unwrap(), board);
+
+        let pos = XOPos::index(7)?;
+        let bytes = bincode::serialize(&pos).unwrap();
+        assert_eq!(bytes, [7]);
+        assert_eq!(bincode::deserialize::<XOPos>(&bytes).unwrap(), pos);
+        Ok(())
+    }
+
+    #[test]
+    fn misere() -> XOResult {
+        for notation in ["XX./OO./... x - m", "XXX/OO./... o o m"].iter() {
+            let board: XOBoard = notation.parse()?;
+            let json = serde_json::to_string(&board).unwrap();
+            assert!(json.ends_with(r#""rules":"Misere"}"#), "{}", json);
+            assert_eq!(serde_json::from_str::<XOBoard>(&json).unwrap(), board);
+            let bytes = bincode::serialize(&board).unwrap();
+            assert_eq!(bincode::deserialize::<XOBoard>(&bytes).unwrap(), board);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reject_impossible_board() {
+        let json = r#"{"cells":["XXX","OOO","..."],"turn":"X","win_state":"X","rules":"Normal"}"#;
+        let err = serde_json::from_str::<XOBoard>(json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&XOBoardError::BothWinError.to_string()));
+
+        let bytes = bincode::serialize(&0b11_u32).unwrap();
+        assert!(bincode::deserialize::<XOBoard>(&bytes).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let err = XOBoardError::PieceCountError {
+            x_count: 2,
+            o_count: 0,
+            turn: XOToken::X,
+        };
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(serde_json::from_str::<XOBoardError>(&json).unwrap(), err);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::board::{ChildrenIter, MoveIter, XOBoard};
+use crate::token::XOTokenWinState;
+use crate::xo_pos::XOPos;
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter};
+
+custom_error! {
+/// Error use when trying to solve a board that doesn't have a game-theoretic value
+///
+/// Currently only use in [`XOBoard::solve`]
+///
+/// [`XOBoard::solve`]: struct.XOBoard.html#method.solve
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOSolveError
+    /// Error of trying to solve a board after its game ended
+    GameEndedError = "attempt to solve a board whose game has already ended",
+    /// Error of trying to solve a board where X and O occupy the same cell
+    InsaneBoardError = "attempt to solve an insane board (X and O occupy the same cell)",
+}
+pub type XOSolveResult<T = ()> = Result<T, XOSolveError>;
+
+/// Game-theoretic value of a board, seen from the player whose turn it is
+///
+/// Ordered from worst to best, so `Loss < Draw < Win`
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum XOValue {
+    /// Player to move will lose against perfect play
+    Loss,
+    /// Both players can force at least a stalemate
+    Draw,
+    /// Player to move can force a win
+    Win,
+}
+
+impl XOValue {
+    /// Return the value seen from the other player (Win <-> Loss, Draw -> Draw)
+    pub fn opposite(self) -> XOValue {
+        match self {
+            XOValue::Loss => XOValue::Win,
+            XOValue::Draw => XOValue::Draw,
+            XOValue::Win => XOValue::Loss,
+        }
+    }
+
+    pub(crate) fn from_score(score: i8) -> XOValue {
+        match score {
+            s if s > 0 => XOValue::Win,
+            0 => XOValue::Draw,
+            _ => XOValue::Loss,
+        }
+    }
+}
+
+impl Display for XOValue {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            XOValue::Loss => write!(f, "Loss"),
+            XOValue::Draw => write!(f, "Draw"),
+            XOValue::Win => write!(f, "Win"),
+        }
+    }
+}
+
+/// Result of solving a board with [`XOBoard::solve`]
+///
+/// [`XOBoard::solve`]: struct.XOBoard.html#method.solve
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct XOSolution {
+    value: XOValue,
+    best_move_bit: u32,
+}
+
+impl XOSolution {
+    pub(crate) fn new(value: XOValue, best_move_bit: u32) -> Self {
+        XOSolution {
+            value,
+            best_move_bit,
+        }
+    }
+
+    /// Game-theoretic value for the player whose turn it is
+    pub fn value(self) -> XOValue {
+        self.value
+    }
+
+    /// Optimal moves as a bit mask, using the same cell layout as [`XOBoard::x_bit`]
+    ///
+    /// [`XOBoard::x_bit`]: struct.XOBoard.html#method.x_bit
+    pub fn best_move_bit(self) -> u32 {
+        self.best_move_bit
+    }
+
+    /// Optimal moves in index order (every one of them achieve [`XOSolution::value`])
+    ///
+    /// [`XOSolution::value`]: struct.XOSolution.html#method.value
+    pub fn best_moves(self) -> Vec<XOPos> {
+        MoveIter::new(self.best_move_bit).collect()
+    }
+}
+
+impl XOBoard {
+    /// Solve the board with negamax (alpha-beta) search
+    ///
+    /// Return the game-theoretic value for [`XOBoard::turn`]
+    /// along with every move that achieve it.
+    /// Boards already decided by their win state bits are treated as terminal.
+    ///
+    /// # Error
+    /// May return Err variant [`XOSolveError::InsaneBoardError`] if [`XOBoard::check_sanity`] fail
+    ///
+    /// May return Err variant [`XOSolveError::GameEndedError`] if the game has already ended
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos, XOValue};
+    ///
+    /// let solution = XOBoard::empty().solve()?;
+    /// assert_eq!(solution.value(), XOValue::Draw);
+    /// assert_eq!(solution.best_moves().len(), 9);
+    ///
+    /// // X X .
+    /// // O O .
+    /// // . . .
+    /// let board = XOBoard::empty()
+    ///     .play(XOPos::index(0)?)?
+    ///     .play(XOPos::index(3)?)?
+    ///     .play(XOPos::index(1)?)?
+    ///     .play(XOPos::index(4)?)?;
+    /// let solution = board.solve()?;
+    /// assert_eq!(solution.value(), XOValue::Win);
+    /// assert_eq!(solution.best_moves()[0].as_index(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::turn`]: struct.XOBoard.html#method.turn
+    /// [`XOBoard::check_sanity`]: struct.XOBoard.html#method.check_sanity
+    /// [`XOSolveError::InsaneBoardError`]: enum.XOSolveError.html#variant.InsaneBoardError
+    /// [`XOSolveError::GameEndedError`]: enum.XOSolveError.html#variant.GameEndedError
+    pub fn solve(self) -> XOSolveResult<XOSolution> {
+        if !self.check_sanity() {
+            return Err(XOSolveError::InsaneBoardError);
+        }
+        if self.game_ended() {
+            return Err(XOSolveError::GameEndedError);
+        }
+
+        let moves = self
+            .legal_moves()
+            .map(|pos| 0b1 << pos.as_index())
+            .zip(self.children());
+        let (value, best_move_bit) = solve_children(moves);
+        Ok(XOSolution::new(value, best_move_bit))
+    }
+}
+
+/// Position that can be searched by `negamax`, scored for the player to move
+pub(crate) trait SearchPosition: Copy {
+    type Children: Iterator<Item = Self>;
+
+    /// Score (-1, 0, 1) if the game has ended, `None` if there's a move left to play
+    fn terminal_score(self) -> Option<i8>;
+
+    /// Every position reachable by one legal move
+    fn children(self) -> Self::Children;
+}
+
+impl SearchPosition for XOBoard {
+    type Children = ChildrenIter;
+
+    fn terminal_score(self) -> Option<i8> {
+        match self.win_state() {
+            Some(XOTokenWinState::Stale) => Some(0),
+            Some(winner) if winner == XOTokenWinState::from(self.turn()) => Some(1),
+            Some(_) => Some(-1),
+            None if self.free_bit() == 0 => Some(0),
+            None => None,
+        }
+    }
+
+    fn children(self) -> ChildrenIter {
+        XOBoard::children(self)
+    }
+}
+
+/// Value of the position whose moves are `moves`, pairs of a move's bit and the position
+/// it lead to, along with the bits of every move that achieve it
+///
+/// A position without move is a stalemate.
+pub(crate) fn solve_children<P: SearchPosition>(
+    moves: impl Iterator<Item = (u32, P)>,
+) -> (XOValue, u32) {
+    let mut best_score = i8::MIN;
+    let mut best_move_bit = 0;
+    for (move_bit, child) in moves {
+        let score = -negamax(child, -1, 1);
+        if score > best_score {
+            best_score = score;
+            best_move_bit = 0;
+        }
+        if score == best_score {
+            best_move_bit |= move_bit;
+        }
+    }
+
+    if best_move_bit == 0 {
+        best_score = 0;
+    }
+    (XOValue::from_score(best_score), best_move_bit)
+}
+
+/// Score (-1, 0, 1) of `position` for the player to move, searched inside window (alpha, beta)
+fn negamax<P: SearchPosition>(position: P, mut alpha: i8, beta: i8) -> i8 {
+    if let Some(score) = position.terminal_score() {
+        return score;
+    }
+
+    let mut best = -1;
+    for child in position.children() {
+        let score = -negamax(child, -beta, -alpha);
+        if score > best {
+            best = score;
+            if best > alpha {
+                alpha = best;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XORules;
+    use crate::token::XOToken::*;
+    use crate::XOResult;
+
+    #[test]
+    fn empty_board_is_draw() -> XOResult {
+        let solution = XOBoard::empty().solve()?;
+        assert_eq!(solution.value(), XOValue::Draw);
+        assert_eq!(solution.best_move_bit(), 0b111_111_111);
+        Ok(())
+    }
+
+    #[test]
+    fn opposite_corners() -> XOResult {
+        /*
+        X . .
+        . O .
+        . . X
+        O to move, any edge draws while corners lose
+        */
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(4)?)?
+            .play(XOPos::index(8)?)?;
+        let solution = board.solve()?;
+        assert_eq!(solution.value(), XOValue::Draw);
+        assert_eq!(solution.best_move_bit(), 0b010_101_010);
+
+        let board = board.play(XOPos::index(2)?)?;
+        assert_eq!(board.solve()?.value(), XOValue::Win);
+        Ok(())
+    }
+
+    #[test]
+    fn forced_loss() -> XOResult {
+        /*
+        X O .
+        . X .
+        X . O
+        O to move, but X threaten both 2 and 3
+        */
+        let mut board = XOBoard::empty();
+        for &index in [0, 1, 4, 8, 6].iter() {
+            board = board.play(XOPos::index(index)?)?;
+        }
+        let solution = board.solve()?;
+        assert_eq!(solution.value(), XOValue::Loss);
+        assert_eq!(solution.best_move_bit(), 0b010_101_100);
+        Ok(())
+    }
+
+    #[test]
+    fn misere() -> XOResult {
+        let board = XOBoard::empty_with_rules(XORules::Misere);
+        let solution = board.solve()?;
+        assert_eq!(solution.value(), XOValue::Draw);
+        assert_eq!(board.lookup(), None);
+
+        // X X .
+        // O O .
+        // . . .
+        // X must avoid completing the top row
+        let mut board = board;
+        for &index in [0, 3, 1, 4].iter() {
+            board = board.play(XOPos::index(index)?)?;
+        }
+        assert_eq!(board.solve()?.best_move_bit() & 0b1 << 2, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn unsolvable() -> XOResult {
+        let ended = XOBoard::from_maybe_token_array([
+            Some(X),
+            Some(X),
+            Some(X),
+            Some(O),
+            Some(O),
+            None,
+            None,
+            None,
+            None,
+        ]);
+        let ended = ended.set_win_state(ended.evaluate_winner());
+        assert_eq!(ended.solve(), Err(XOSolveError::GameEndedError));
+
+        let insane = XOBoard::new(0b1 | 0b1 << 9);
+        assert_eq!(insane.solve(), Err(XOSolveError::InsaneBoardError));
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use crate::board::XOBoard;
+use crate::xo_pos::XOPos;
+
+/// Symmetry of the xo grid, one of the 8 elements of the dihedral group D4
+///
+/// Rotations are clockwise. Every transform only move cells around,
+/// so turn and win state bits of [`XOBoard`] are preserved.
+///
+/// [`XOBoard`]: struct.XOBoard.html
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum XOTransform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Swap left and right column
+    Mirror,
+    /// Swap top and bottom row
+    Flip,
+    /// Reflect across the top-left to bottom-right diagonal
+    Transpose,
+    /// Reflect across the top-right to bottom-left diagonal
+    AntiTranspose,
+}
+
+use XOTransform::*;
+
+/// Exchange bits in `mask` with bits `delta` places above them
+const fn delta_swap(bits: u32, mask: u32, delta: u32) -> u32 {
+    let t = ((bits >> delta) ^ bits) & mask;
+    bits ^ t ^ (t << delta)
+}
+
+// masks are duplicated for both x and o part of the bit board
+const MIRROR_MASK: u32 = 0o111 | 0o111 << 9;
+const FLIP_MASK: u32 = 0o007 | 0o007 << 9;
+const TRANSPOSE_MASK_2: u32 = 0o042 | 0o042 << 9;
+const TRANSPOSE_MASK_4: u32 = 0o004 | 0o004 << 9;
+
+const fn mirror(bits: u32) -> u32 {
+    delta_swap(bits, MIRROR_MASK, 2)
+}
+
+const fn flip(bits: u32) -> u32 {
+    delta_swap(bits, FLIP_MASK, 6)
+}
+
+const fn transpose(bits: u32) -> u32 {
+    delta_swap(delta_swap(bits, TRANSPOSE_MASK_2, 2), TRANSPOSE_MASK_4, 4)
+}
+
+impl XOTransform {
+    /// Every transform, starting with [`XOTransform::Identity`]
+    ///
+    /// [`XOTransform::Identity`]: enum.XOTransform.html#variant.Identity
+    pub const ALL: [XOTransform; 8] = [
+        Identity,
+        Rotate90,
+        Rotate180,
+        Rotate270,
+        Mirror,
+        Flip,
+        Transpose,
+        AntiTranspose,
+    ];
+
+    /// Return the transform that undo this transform
+    pub fn inverse(self) -> XOTransform {
+        match self {
+            Rotate90 => Rotate270,
+            Rotate270 => Rotate90,
+            other => other,
+        }
+    }
+
+    /// Return the transform equivalent to applying `self` then `next`
+    ///
+    /// ```rust
+    /// use bitboard_xo::XOTransform::*;
+    ///
+    /// assert_eq!(Rotate90.then(Rotate90), Rotate180);
+    /// assert_eq!(Transpose.then(Mirror), Rotate90);
+    /// ```
+    pub fn then(self, next: XOTransform) -> XOTransform {
+        // cells 0 and 1 aren't mapped to the same pair of cells by any two distinct transforms
+        const PROBE: u32 = 0b11;
+        let target = next.apply(self.apply(PROBE));
+        *XOTransform::ALL
+            .iter()
+            .find(|transform| transform.apply(PROBE) == target)
+            .expect("D4 is closed under composition")
+    }
+
+    /// Move cells of every 9 bit group in `bits` (the layout of [`XOBoard::new`])
+    ///
+    /// [`XOBoard::new`]: struct.XOBoard.html#method.new
+    pub(crate) const fn apply(self, bits: u32) -> u32 {
+        match self {
+            Identity => bits,
+            Rotate90 => mirror(transpose(bits)),
+            Rotate180 => flip(mirror(bits)),
+            Rotate270 => flip(transpose(bits)),
+            Mirror => mirror(bits),
+            Flip => flip(bits),
+            Transpose => transpose(bits),
+            AntiTranspose => flip(mirror(transpose(bits))),
+        }
+    }
+}
+
+impl XOBoard {
+    /// Apply `transform` to the cells, keeping turn and win state bits
+    pub fn transform(self, transform: XOTransform) -> XOBoard {
+        XOBoard::new(transform.apply(self.bits()))
+    }
+
+    /// Rotate the board clockwise by a quarter turn
+    pub fn rotate(self) -> XOBoard {
+        self.transform(Rotate90)
+    }
+
+    /// Swap left and right column of the board
+    pub fn mirror(self) -> XOBoard {
+        self.transform(Mirror)
+    }
+
+    /// Reflect the board across its top-left to bottom-right diagonal
+    pub fn transpose(self) -> XOBoard {
+        self.transform(Transpose)
+    }
+
+    /// Iterate through all 8 symmetric variants of the board,
+    /// in the order of [`XOTransform::ALL`] (which may contain duplicate)
+    ///
+    /// [`XOTransform::ALL`]: enum.XOTransform.html#associatedconstant.ALL
+    pub fn symmetries(self) -> SymmetryIter {
+        SymmetryIter {
+            board: self,
+            transforms: XOTransform::ALL.iter(),
+        }
+    }
+
+    /// Return the canonical form of this board, the same for every symmetric variant,
+    /// and the transform mapping this board to it
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos};
+    ///
+    /// let board = XOBoard::empty().play(XOPos::index(8)?)?;
+    /// let (canonical, transform) = board.canonical();
+    ///
+    /// assert_eq!(board.transform(transform), canonical);
+    /// assert!(board.symmetries().all(|variant| variant.canonical().0 == canonical));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn canonical(self) -> (XOBoard, XOTransform) {
+        XOTransform::ALL
+            .iter()
+            .map(|&transform| (self.transform(transform), transform))
+            .min_by_key(|(board, _)| board.bits())
+            .expect("there are always 8 transforms")
+    }
+}
+
+impl XOPos {
+    /// Apply `transform` to this position, matching [`XOBoard::transform`]
+    ///
+    /// [`XOBoard::transform`]: struct.XOBoard.html#method.transform
+    pub fn transform(self, transform: XOTransform) -> XOPos {
+        XOPos::new_unchecked(transform.apply(0b1 << self.as_index()).trailing_zeros())
+    }
+}
+
+/// Iterator through symmetric variants of [`XOBoard`]
+///
+/// # Construction
+/// This struct can't be construct directly but can construct with [`XOBoard::symmetries`]
+///
+/// [`XOBoard`]: struct.XOBoard.html
+/// [`XOBoard::symmetries`]: struct.XOBoard.html#method.symmetries
+#[derive(Clone, Debug)]
+pub struct SymmetryIter {
+    board: XOBoard,
+    transforms: std::slice::Iter<'static, XOTransform>,
+}
+
+impl Iterator for SymmetryIter {
+    type Item = XOBoard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board = self.board;
+        self.transforms
+            .next()
+            .map(|&transform| board.transform(transform))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.transforms.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SymmetryIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn transform_cells() -> XOResult {
+        let expected = [
+            (Identity, [0, 1, 2, 3, 4, 5, 6, 7, 8]),
+            (Rotate90, [2, 5, 8, 1, 4, 7, 0, 3, 6]),
+            (Rotate180, [8, 7, 6, 5, 4, 3, 2, 1, 0]),
+            (Rotate270, [6, 3, 0, 7, 4, 1, 8, 5, 2]),
+            (Mirror, [2, 1, 0, 5, 4, 3, 8, 7, 6]),
+            (Flip, [6, 7, 8, 3, 4, 5, 0, 1, 2]),
+            (Transpose, [0, 3, 6, 1, 4, 7, 2, 5, 8]),
+            (AntiTranspose, [8, 5, 2, 7, 4, 1, 6, 3, 0]),
+        ];
+        for (transform, image) in expected.iter() {
+            for index in 0..9 {
+                let pos = XOPos::index(index)?.transform(*transform);
+                assert_eq!(pos.as_index(), image[index as usize], "{:?}", transform);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn group_laws() {
+        for &a in XOTransform::ALL.iter() {
+            assert_eq!(a.then(a.inverse()), Identity);
+            for &b in XOTransform::ALL.iter() {
+                let bits = 0o123_456;
+                assert_eq!(a.then(b).apply(bits), b.apply(a.apply(bits)));
+            }
+        }
+    }
+
+    #[test]
+    fn keep_turn_and_win_state() -> XOResult {
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(3)?)?
+            .play(XOPos::index(1)?)?
+            .play(XOPos::index(4)?)?
+            .play(XOPos::index(2)?)?;
+        for variant in board.symmetries() {
+            assert_eq!(variant.turn(), board.turn());
+            assert_eq!(variant.win_state(), board.win_state());
+            assert_eq!(variant.evaluate_winner(), board.evaluate_winner());
+        }
+        Ok(())
+    }
+}
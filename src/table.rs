@@ -0,0 +1,301 @@
+use crate::board::XOBoard;
+use crate::solver::{XOSolution, XOValue};
+use crate::token::{XOToken, XOTokenWinState};
+
+/// Number of distinct positions reachable from [`XOBoard::empty`] by legal play
+/// (including the empty board and every finished board)
+///
+/// [`XOBoard::empty`]: struct.XOBoard.html#method.empty
+pub const REACHABLE_POSITIONS: usize = 5478;
+
+/// Number of boards where every cell is either empty, X or O (3^9)
+pub(crate) const TERNARY_BOARDS: usize = 19683;
+
+/// `TERNARY_WEIGHT[bits]` is the sum of 3^index for every index set in 9 bit mask `bits`
+pub(crate) const TERNARY_WEIGHT: [u16; 512] = ternary_weight();
+
+const fn ternary_weight() -> [u16; 512] {
+    let mut weight = [0_u16; 512];
+    let mut bits = 1;
+    while bits < 512 {
+        let lowest = (bits as u32).trailing_zeros();
+        weight[bits] = weight[bits & (bits - 1)] + 3_u16.pow(lowest);
+        bits += 1;
+    }
+    weight
+}
+
+/// Base-3 rank of the cells, each cell is a digit (0 empty, 1 X, 2 O) starting from index 0
+pub(crate) fn ternary_rank(x_bit: u32, o_bit: u32) -> usize {
+    TERNARY_WEIGHT[x_bit as usize] as usize + 2 * TERNARY_WEIGHT[o_bit as usize] as usize
+}
+
+// layout of table entry:
+// bit 0-8: best move mask, bit 9-10: value (0 = unreachable), bit 11: game ended
+const VALUE_SHIFT: u32 = 9;
+const ENDED_BIT: u16 = 0b1 << 11;
+const LOSS: u16 = 1;
+const DRAW: u16 = 2;
+const WIN: u16 = 3;
+
+/// Value and best moves of every ternary board, indexed by [`ternary_rank`]
+// only read by other tables at compile time, the runtime copy is `SOLUTION_TABLE`
+#[allow(clippy::large_const_arrays)]
+pub(crate) const SOLUTIONS: [u16; TERNARY_BOARDS] = solution_table();
+static SOLUTION_TABLE: [u16; TERNARY_BOARDS] = SOLUTIONS;
+
+/// Check if 9 bit mask `b` contain a line
+pub(crate) const fn has_line(b: u32) -> bool {
+    b & b >> 1 & b >> 2 & 0o111 != 0
+        || b & b >> 3 & b >> 6 & 7 != 0
+        || b & 0o124 == 0o124
+        || b & 0o421 == 0o421
+}
+
+const fn solution_table() -> [u16; TERNARY_BOARDS] {
+    let mut table = [0_u16; TERNARY_BOARDS];
+    let mut x_of = [0_u16; TERNARY_BOARDS];
+    let mut o_of = [0_u16; TERNARY_BOARDS];
+
+    let mut rank = 0;
+    while rank < TERNARY_BOARDS {
+        let mut rest = rank;
+        let mut index = 0;
+        while index < 9 {
+            match rest % 3 {
+                1 => x_of[rank] |= 0b1 << index,
+                2 => o_of[rank] |= 0b1 << index,
+                _ => {}
+            }
+            rest /= 3;
+            index += 1;
+        }
+        rank += 1;
+    }
+
+    // playing a move always increase the rank, so children are solved before their parent
+    let mut rank = TERNARY_BOARDS;
+    while rank > 0 {
+        rank -= 1;
+        let x = x_of[rank] as u32;
+        let o = o_of[rank] as u32;
+        let x_count = x.count_ones();
+        let o_count = o.count_ones();
+        if x_count != o_count && x_count != o_count + 1 {
+            continue;
+        }
+
+        if has_line(x) || has_line(o) {
+            table[rank] = ENDED_BIT | LOSS << VALUE_SHIFT;
+            continue;
+        }
+        let free = !(x | o) & 0b111_111_111;
+        if free == 0 {
+            table[rank] = ENDED_BIT | DRAW << VALUE_SHIFT;
+            continue;
+        }
+
+        // digit of the player to move
+        let digit = if x_count == o_count { 1 } else { 2 };
+        let mut best = 0;
+        let mut best_move_bit = 0;
+        let mut index = 0;
+        while index < 9 {
+            if free >> index & 0b1 == 1 {
+                let child = table[rank + digit * 3_usize.pow(index)] >> VALUE_SHIFT & 0b11;
+                // value of the child is seen from the opponent
+                let value = WIN + LOSS - child;
+                if value > best {
+                    best = value;
+                    best_move_bit = 0;
+                }
+                if value == best {
+                    best_move_bit |= 0b1 << index;
+                }
+            }
+            index += 1;
+        }
+        table[rank] = best << VALUE_SHIFT | best_move_bit;
+    }
+
+    // keep only positions reachable from the empty board
+    let mut reachable = [false; TERNARY_BOARDS];
+    reachable[0] = true;
+    let mut rank = 0;
+    while rank < TERNARY_BOARDS {
+        if !reachable[rank] {
+            table[rank] = 0;
+        } else if table[rank] & ENDED_BIT == 0 {
+            let free = !(x_of[rank] | o_of[rank]) & 0b111_111_111;
+            let digit = if (x_of[rank] as u32).count_ones() == (o_of[rank] as u32).count_ones() {
+                1
+            } else {
+                2
+            };
+            let mut index = 0;
+            while index < 9 {
+                if free >> index & 0b1 == 1 {
+                    reachable[rank + digit * 3_usize.pow(index)] = true;
+                }
+                index += 1;
+            }
+        }
+        rank += 1;
+    }
+
+    table
+}
+
+/// Bit set of every 9 bit mask containing a line
+static LINE_TABLE: [u64; 8] = line_table();
+
+const fn line_table() -> [u64; 8] {
+    let mut table = [0_u64; 8];
+    let mut bits = 0;
+    while bits < 512 {
+        if has_line(bits) {
+            table[(bits >> 6) as usize] |= 0b1 << (bits & 0o77);
+        }
+        bits += 1;
+    }
+    table
+}
+
+/// Check if 9 bit mask `bits` contain a line, same as [`has_line`] but with a table lookup
+pub(crate) fn has_line_lookup(bits: u32) -> bool {
+    LINE_TABLE[(bits >> 6) as usize] >> (bits & 0o77) & 0b1 == 1
+}
+
+/// Check if X at `x_bit` and O at `o_bit` can be reached by legal play from the empty board
+pub(crate) fn reachable_cells(x_bit: u32, o_bit: u32) -> bool {
+    SOLUTION_TABLE[ternary_rank(x_bit, o_bit)] != 0
+}
+
+impl XOBoard {
+    /// Look up value and best moves in a table embedded at compile time,
+    /// agreeing with [`XOBoard::solve`] without any search
+    ///
+    /// Unlike [`XOBoard::solve`], finished boards also have a solution
+    /// (without any best move).
+    ///
+    /// Return `None` if this board can't be reached from [`XOBoard::empty`] by [`XOBoard::play`],
+    /// including boards with turn or win state bits that disagree with their cells.
+    /// The table hold values under normal rules, so boards played with [`XORules::Misere`]
+    /// also return `None` even though they're reachable (use [`XOBoard::solve`] for those).
+    ///
+    /// ```rust
+    /// # use bitboard_xo::XOResult;
+    /// # fn main() -> XOResult {
+    /// use bitboard_xo::{XOBoard, XOPos, XOValue};
+    ///
+    /// let board = XOBoard::empty().play(XOPos::index(1)?)?;
+    /// assert_eq!(board.lookup(), Some(board.solve()?));
+    ///
+    /// // O can't start from empty board
+    /// assert_eq!(XOBoard::empty().swap_turn().lookup(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`XOBoard::solve`]: struct.XOBoard.html#method.solve
+    /// [`XOBoard::empty`]: struct.XOBoard.html#method.empty
+    /// [`XOBoard::play`]: struct.XOBoard.html#method.play
+    /// [`XORules::Misere`]: enum.XORules.html#variant.Misere
+    pub fn lookup(self) -> Option<XOSolution> {
+        if !self.check_sanity() {
+            return None;
+        }
+
+        let cell_bits = self.x_bit() | self.o_bit() << 9;
+        let entry = SOLUTION_TABLE[ternary_rank(self.x_bit(), self.o_bit())];
+        let value = match entry >> VALUE_SHIFT & 0b11 {
+            LOSS => XOValue::Loss,
+            DRAW => XOValue::Draw,
+            WIN => XOValue::Win,
+            _ => return None,
+        };
+
+        let turn = if self.x_bit().count_ones() == self.o_bit().count_ones() {
+            XOToken::X
+        } else {
+            XOToken::O
+        };
+        let win_state = match (entry & ENDED_BIT != 0, value) {
+            (false, _) => None,
+            (true, XOValue::Draw) => Some(XOTokenWinState::Stale),
+            (true, _) => Some(XOTokenWinState::from(turn.opposite_token())),
+        };
+        let expected = XOBoard::new(cell_bits).set_win_state(win_state);
+        let expected = match turn {
+            XOToken::X => expected,
+            XOToken::O => expected.swap_turn(),
+        };
+        if expected != self {
+            return None;
+        }
+
+        Some(XOSolution::new(value, (entry & 0b111_111_111) as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn reachable_boards() -> Vec<XOBoard> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty()];
+        let mut boards = Vec::new();
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                boards.push(board);
+                stack.extend(board.children());
+            }
+        }
+        boards
+    }
+
+    #[test]
+    fn table_cover_reachable_positions() {
+        let boards = reachable_boards();
+        assert_eq!(boards.len(), REACHABLE_POSITIONS);
+
+        let reachable_ranks: HashSet<_> = boards
+            .iter()
+            .map(|board| ternary_rank(board.x_bit(), board.o_bit()))
+            .collect();
+        assert_eq!(reachable_ranks.len(), REACHABLE_POSITIONS);
+        for (rank, &entry) in SOLUTION_TABLE.iter().enumerate() {
+            assert_eq!(entry != 0, reachable_ranks.contains(&rank));
+        }
+    }
+
+    #[test]
+    fn table_agree_with_evaluate_winner_and_solve() {
+        for board in reachable_boards() {
+            let solution = board.lookup().expect("reachable board is in the table");
+            assert_eq!(board.win_state(), board.evaluate_winner());
+            match board.evaluate_winner() {
+                None => assert_eq!(board.solve(), Ok(solution)),
+                Some(XOTokenWinState::Stale) => {
+                    assert_eq!(solution.value(), XOValue::Draw);
+                    assert_eq!(solution.best_move_bit(), 0);
+                }
+                Some(_) => {
+                    assert_eq!(solution.value(), XOValue::Loss);
+                    assert_eq!(solution.best_move_bit(), 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reject_inconsistent_bits() {
+        let board = XOBoard::empty();
+        assert!(board.lookup().is_some());
+        assert_eq!(board.swap_turn().lookup(), None);
+        assert_eq!(board.set_win_state(Some(XOTokenWinState::X)).lookup(), None);
+        assert_eq!(XOBoard::new(0b1 << 31).lookup(), None);
+    }
+}
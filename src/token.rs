@@ -1,7 +1,8 @@
 use std::fmt::{self, Display, Formatter};
 
 /// Represent X,O of xo game
-#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum XOToken {
     X,
     O,
@@ -28,8 +29,45 @@ impl Display for XOToken {
     }
 }
 
+/// Player of a game where tokens don't belong to a player, in order of play
+///
+/// Used by [notakto], where both players place X, and [wild xo], where either may place X or O
+///
+/// [notakto]: notakto/index.html
+/// [wild xo]: wild/index.html
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Player {
+    /// Player who made the first move
+    First,
+    /// Player who made the second move
+    Second,
+}
+
+impl Player {
+    /// Return the other player (First -> Second, Second -> First)
+    ///
+    /// This method does not mutate
+    pub fn opposite(self) -> Player {
+        match self {
+            Player::First => Player::Second,
+            Player::Second => Player::First,
+        }
+    }
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Player::First => write!(f, "First"),
+            Player::Second => write!(f, "Second"),
+        }
+    }
+}
+
 /// Represent type of ending of xo game, either `X` (win), `O` (win), or `State`(mate)
-#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum XOTokenWinState {
     /// Represent when game ended with X as winner
     X,
@@ -0,0 +1,295 @@
+//! Game API where finished games can't be played, checked at compile time
+//!
+//! A game is either [`Game<Ongoing>`] or [`Game<Finished>`]. Only ongoing games have
+//! [`Game::play`], which consume the game and return a [`GameState`] holding either kind,
+//! so the only error left is playing on an occupied cell.
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::typestate::{Game, GameState};
+//! use bitboard_xo::{XOPos, XOTokenWinState};
+//!
+//! let mut game = Game::new();
+//! for &index in [0, 3, 1, 4].iter() {
+//!     game = match game.play(XOPos::index(index)?)? {
+//!         GameState::Ongoing(game) => game,
+//!         GameState::Finished(_) => unreachable!(),
+//!     };
+//! }
+//!
+//! match game.play(XOPos::index(2)?)? {
+//!     GameState::Ongoing(_) => unreachable!(),
+//!     // `finished.play(...)` doesn't compile
+//!     GameState::Finished(finished) => assert_eq!(finished.win_state(), XOTokenWinState::X),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Playing a finished game is a compile error:
+//!
+//! ```compile_fail
+//! use bitboard_xo::typestate::{Finished, Game};
+//! use bitboard_xo::XOPos;
+//!
+//! fn play_finished(game: Game<Finished>) {
+//!     let _ = game.play(XOPos::index(0).unwrap());
+//! }
+//! ```
+//!
+//! [`Game<Ongoing>`]: struct.Game.html
+//! [`Game<Finished>`]: struct.Game.html
+//! [`Game::play`]: struct.Game.html#method.play
+//! [`GameState`]: enum.GameState.html
+
+use crate::board::{MoveIter, XOBoard};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::validation::{XOBoardError, XOBoardResult};
+use crate::xo_pos::XOPos;
+use crate::XO;
+
+use custom_error::custom_error;
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+
+custom_error! {
+/// Error of trying to play at position that isn't empty, the only error of [`Game::play`]
+///
+/// [`Game::play`]: struct.Game.html#method.play
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOOccupiedError{index: u32} = "Position index {index} has already been play"
+}
+
+/// State of [`Game`] where the game hasn't ended
+///
+/// [`Game`]: struct.Game.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ongoing;
+
+/// State of [`Game`] where the game has ended
+///
+/// [`Game`]: struct.Game.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Finished;
+
+/// Xo game with its state (ongoing or finished) in the type, the same size as [`XOBoard`]
+///
+/// [`XOBoard`]: ../struct.XOBoard.html
+#[must_use = "Game's method doesn't mutate but return a new game"]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct Game<S> {
+    board: XOBoard,
+    state: PhantomData<S>,
+}
+
+/// Game of either state, returned by [`Game::play`]
+///
+/// [`Game::play`]: struct.Game.html#method.play
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GameState {
+    Ongoing(Game<Ongoing>),
+    Finished(Game<Finished>),
+}
+
+impl<S> Game<S> {
+    fn wrap(board: XOBoard) -> Self {
+        Game {
+            board,
+            state: PhantomData,
+        }
+    }
+
+    /// Retrieve the board
+    pub fn board(self) -> XOBoard {
+        self.board
+    }
+
+    /// Retrieve the board as [`XO`]
+    ///
+    /// [`XO`]: ../struct.XO.html
+    pub fn game(self) -> XO {
+        XO::from_board(self.board)
+    }
+}
+
+impl Game<Ongoing> {
+    /// Construct a new game with empty board and X as starting player.
+    pub fn new() -> Self {
+        Game::wrap(XOBoard::empty())
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(self) -> XOToken {
+        self.board.turn()
+    }
+
+    /// Iterate through positions that can be played
+    pub fn legal_moves(self) -> MoveIter {
+        self.board.legal_moves()
+    }
+
+    /// Play the game, consuming it
+    ///
+    /// # Error
+    /// Return [`XOOccupiedError`] if `pos` has already been played
+    ///
+    /// [`XOOccupiedError`]: struct.XOOccupiedError.html
+    pub fn play(self, pos: XOPos) -> Result<GameState, XOOccupiedError> {
+        if !self.board.check_free_position(pos) {
+            return Err(XOOccupiedError {
+                index: pos.as_index(),
+            });
+        }
+        Ok(GameState::wrap(self.board.play_unchecked(pos)))
+    }
+}
+
+impl Default for Game<Ongoing> {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game<Finished> {
+    /// Who won the game, or [`XOTokenWinState::Stale`]
+    ///
+    /// [`XOTokenWinState::Stale`]: ../enum.XOTokenWinState.html#variant.Stale
+    pub fn win_state(self) -> XOTokenWinState {
+        self.board
+            .win_state()
+            .expect("finished game always has win state")
+    }
+}
+
+impl<S> Display for Game<S> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.board.fmt(f)
+    }
+}
+
+impl GameState {
+    /// Wrap valid `board` into the game of its state, decided by `game_ended`
+    fn wrap(board: XOBoard) -> Self {
+        if board.game_ended() {
+            GameState::Finished(Game::wrap(board))
+        } else {
+            GameState::Ongoing(Game::wrap(board))
+        }
+    }
+
+    /// Retrieve the board
+    pub fn board(self) -> XOBoard {
+        match self {
+            GameState::Ongoing(game) => game.board(),
+            GameState::Finished(game) => game.board(),
+        }
+    }
+}
+
+impl TryFrom<XOBoard> for GameState {
+    type Error = XOBoardError;
+
+    /// Wrap `board` into the game of its state, decided by [`XOBoard::game_ended`]
+    ///
+    /// # Error
+    /// Return error of [`XOBoard::validate`] if `board` is invalid,
+    /// since its win state bits wouldn't match its state
+    ///
+    /// [`XOBoard::game_ended`]: ../struct.XOBoard.html#method.game_ended
+    /// [`XOBoard::validate`]: ../struct.XOBoard.html#method.validate
+    fn try_from(board: XOBoard) -> XOBoardResult<Self> {
+        board.validate()?;
+        Ok(GameState::wrap(board))
+    }
+}
+
+impl TryFrom<XO> for GameState {
+    type Error = XOBoardError;
+
+    /// Same as converting [`XO::board`]
+    ///
+    /// [`XO::board`]: ../struct.XO.html#method.board
+    fn try_from(game: XO) -> XOBoardResult<Self> {
+        GameState::try_from(game.board())
+    }
+}
+
+impl From<GameState> for XO {
+    fn from(state: GameState) -> Self {
+        XO::from_board(state.board())
+    }
+}
+
+impl<S> From<Game<S>> for XO {
+    fn from(game: Game<S>) -> Self {
+        game.game()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn zero_cost() {
+        assert_eq!(
+            std::mem::size_of::<Game<Ongoing>>(),
+            std::mem::size_of::<XOBoard>()
+        );
+    }
+
+    #[test]
+    fn agree_with_xo() -> XOResult {
+        let mut game = Game::new();
+        let mut xo = XO::new();
+        for &index in [4, 0, 2, 6, 3, 5, 1, 7].iter() {
+            xo.play(XOPos::index(index)?)?;
+            game = match game.play(XOPos::index(index)?)? {
+                GameState::Ongoing(game) => game,
+                GameState::Finished(_) => panic!("game shouldn't end yet"),
+            };
+            assert_eq!(XO::from(game), xo);
+        }
+
+        assert_eq!(
+            game.play(XOPos::index(4)?),
+            Err(XOOccupiedError { index: 4 })
+        );
+        let finished = match game.play(XOPos::index(8)?)? {
+            GameState::Finished(finished) => finished,
+            GameState::Ongoing(_) => panic!("game should end"),
+        };
+        assert_eq!(finished.win_state(), XOTokenWinState::Stale);
+        assert_eq!(
+            GameState::try_from(XO::from(finished)),
+            Ok(GameState::Finished(finished))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_board() -> XOResult {
+        // 0b010 isn't a win state encoding
+        assert_eq!(
+            GameState::try_from(XOBoard::new(0b010 << 19)),
+            Err(XOBoardError::InvalidWinStateError { bits: 0b010 })
+        );
+
+        // three in a row without win state bits
+        let mut board = XOBoard::empty();
+        for &index in [0, 3, 1, 4].iter() {
+            board = board.play(XOPos::index(index)?)?;
+        }
+        let board = board.play_unchecked(XOPos::index(2)?).set_win_state(None);
+        assert_eq!(
+            GameState::try_from(board),
+            Err(XOBoardError::WinStateMismatchError)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+//! Ultimate xo: a 3x3 grid of xo boards, where winning a board claim its cell of the meta board
+//!
+//! The cell played in a board send the opponent to the board at the same position.
+//! When that board has already finished (won or full), the opponent may choose any board.
+//! The game is won by a line on the meta board, and is a stalemate once every board
+//! finished without such line.
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::ultimate::{UltimatePos, UltimateXO, XOUltimateError};
+//! use bitboard_xo::XOPos;
+//!
+//! let mut game = UltimateXO::new();
+//! let center = XOPos::index(4)?;
+//! let corner = XOPos::index(0)?;
+//!
+//! // X play at the top left cell of the center board, sending O to the top left board
+//! game.play(UltimatePos::new(center, corner))?;
+//! assert_eq!(game.next_board(), Some(corner));
+//! assert_eq!(
+//!     game.play(UltimatePos::new(center, center)),
+//!     Err(XOUltimateError::WrongBoardError { board: 4, expected: 0 })
+//! );
+//! game.play(UltimatePos::new(corner, center))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::{XOBoard, XOGameError};
+use crate::token::{XOToken, XOTokenWinState};
+use crate::xo_pos::XOPos;
+
+use custom_error::custom_error;
+use std::fmt::{self, Display, Formatter, Write};
+
+custom_error! {
+/// Error use when trying to do invalid play in [`UltimateXO`]
+///
+/// [`UltimateXO`]: struct.UltimateXO.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOUltimateError
+    /// Error of playing in a board other than the one the opponent sent to
+    WrongBoardError{board: u32, expected: u32} =
+        "attempt to play in board {board}, but must play in board {expected}",
+    /// Error of playing in a board that has already finished
+    BoardFinishedError{board: u32} = "attempt to play in board {board} which has already finished",
+    /// Error of playing on an occupied cell or after the game ended
+    GameError{source: XOGameError} = "Error occurred when trying to play (make a move)",
+}
+pub type XOUltimateResult<T = ()> = Result<T, XOUltimateError>;
+
+/// Position in ultimate xo, a cell of one of the 9 boards
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UltimatePos {
+    board: XOPos,
+    cell: XOPos,
+}
+
+impl UltimatePos {
+    /// Create position of `cell` in the board at `board` of the meta board
+    pub fn new(board: XOPos, cell: XOPos) -> Self {
+        UltimatePos { board, cell }
+    }
+
+    /// Position of the board in the meta board
+    pub fn board(self) -> XOPos {
+        self.board
+    }
+
+    /// Position of the cell in its board
+    pub fn cell(self) -> XOPos {
+        self.cell
+    }
+}
+
+/// Representing ultimate xo game, see [module documentation]
+///
+/// Each of the 9 boards is a [`XOBoard`] keeping its own win state,
+/// and the meta board is a [`XOBoard`] with a token at every board that was won.
+///
+/// [module documentation]: index.html
+/// [`XOBoard`]: ../struct.XOBoard.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UltimateXO {
+    boards: [XOBoard; 9],
+    meta: XOBoard,
+    turn: XOToken,
+    next_board: Option<XOPos>,
+    win_state: Option<XOTokenWinState>,
+}
+
+impl UltimateXO {
+    /// Construct a new game with empty boards and X as starting player, who may play anywhere
+    pub fn new() -> Self {
+        UltimateXO {
+            boards: [XOBoard::empty(); 9],
+            meta: XOBoard::empty(),
+            turn: XOToken::X,
+            next_board: None,
+            win_state: None,
+        }
+    }
+
+    /// Play the game! This turn's player will try to play at position `pos`
+    ///
+    /// Return the type of game ending if the game ended after this move, like [`XO::play`]
+    ///
+    /// # Error
+    /// Return [`XOUltimateError::WrongBoardError`] if `pos` isn't in [`UltimateXO::next_board`],
+    /// [`XOUltimateError::BoardFinishedError`] if the board of `pos` has finished, or
+    /// [`XOUltimateError::GameError`] if the cell is occupied or the game has ended
+    ///
+    /// [`XO::play`]: ../struct.XO.html#method.play
+    /// [`XOUltimateError::WrongBoardError`]: enum.XOUltimateError.html#variant.WrongBoardError
+    /// [`UltimateXO::next_board`]: struct.UltimateXO.html#method.next_board
+    /// [`XOUltimateError::BoardFinishedError`]: enum.XOUltimateError.html#variant.BoardFinishedError
+    /// [`XOUltimateError::GameError`]: enum.XOUltimateError.html#variant.GameError
+    pub fn play(&mut self, pos: UltimatePos) -> XOUltimateResult<Option<XOTokenWinState>> {
+        if self.win_state.is_some() {
+            return Err(XOGameError::GameEndedError.into());
+        }
+        let board_index = pos.board.as_index();
+        match self.next_board {
+            Some(expected) if expected != pos.board => {
+                return Err(XOUltimateError::WrongBoardError {
+                    board: board_index,
+                    expected: expected.as_index(),
+                })
+            }
+            _ => {}
+        }
+
+        let board = self.boards[board_index as usize];
+        if board.game_ended() {
+            return Err(XOUltimateError::BoardFinishedError { board: board_index });
+        }
+        // every board keep its own turn bit, align it with the game's turn
+        let board = if board.turn() == self.turn {
+            board
+        } else {
+            board.swap_turn()
+        };
+        let board = board.play(pos.cell)?;
+        self.boards[board_index as usize] = board;
+
+        match board.win_state() {
+            Some(XOTokenWinState::X) => self.meta = self.meta.set(XOToken::X, pos.board),
+            Some(XOTokenWinState::O) => self.meta = self.meta.set(XOToken::O, pos.board),
+            _ => {}
+        }
+        self.win_state = match self.meta.evaluate_winner() {
+            Some(XOTokenWinState::Stale) | None => {
+                if self.boards.iter().all(|board| board.game_ended()) {
+                    Some(XOTokenWinState::Stale)
+                } else {
+                    None
+                }
+            }
+            winner => winner,
+        };
+
+        self.next_board = if self.boards[pos.cell.as_index() as usize].game_ended() {
+            None
+        } else {
+            Some(pos.cell)
+        };
+        self.turn = self.turn.opposite_token();
+        Ok(self.win_state)
+    }
+
+    /// get current turn's play (player who going to play)
+    pub fn turn(&self) -> XOToken {
+        self.turn
+    }
+
+    /// Get information about who (if any) won the game
+    pub fn win_state(&self) -> Option<XOTokenWinState> {
+        self.win_state
+    }
+
+    /// Board the current player must play in, `None` if any unfinished board can be played
+    pub fn next_board(&self) -> Option<XOPos> {
+        self.next_board
+    }
+
+    /// Retrieve the board at `pos` of the meta board
+    pub fn board(&self, pos: XOPos) -> XOBoard {
+        self.boards[pos.as_index() as usize]
+    }
+
+    /// Retrieve the meta board, with a token at every board won by that token
+    pub fn meta_board(&self) -> XOBoard {
+        self.meta
+    }
+
+    /// Every position that can be played
+    pub fn legal_moves(&self) -> Vec<UltimatePos> {
+        if self.win_state.is_some() {
+            return Vec::new();
+        }
+        let boards: Vec<XOPos> = match self.next_board {
+            Some(board) => vec![board],
+            None => (0..9).map(XOPos::new_unchecked).collect(),
+        };
+        boards
+            .into_iter()
+            .flat_map(|board| {
+                self.board(board)
+                    .legal_moves()
+                    .map(move |cell| UltimatePos::new(board, cell))
+            })
+            .collect()
+    }
+}
+
+impl Default for UltimateXO {
+    fn default() -> Self {
+        UltimateXO::new()
+    }
+}
+
+impl Display for UltimateXO {
+    /// Show the 9x9 cells, with boards separated by `|` and `-`
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        if let Some(winner) = self.win_state() {
+            match winner {
+                XOTokenWinState::Stale => writeln!(f, "Game Ended In Stale Mate")?,
+                XOTokenWinState::X | XOTokenWinState::O => {
+                    writeln!(f, "Game Ended: {}'s winner", winner)?
+                }
+            }
+        } else {
+            writeln!(f, "{}'s Turn", self.turn())?;
+        }
+
+        for row in 0..9 {
+            if row != 0 && row % 3 == 0 {
+                writeln!(f, "---+---+---")?;
+            }
+            for col in 0..9 {
+                if col != 0 && col % 3 == 0 {
+                    f.write_char('|')?;
+                }
+                let board = self.boards[row / 3 * 3 + col / 3];
+                match board.token_at(XOPos::new_unchecked((row % 3 * 3 + col % 3) as u32)) {
+                    Some(XOToken::X) => f.write_char('X')?,
+                    Some(XOToken::O) => f.write_char('O')?,
+                    None => f.write_char('.')?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    fn pos(board: u32, cell: u32) -> XOResult<UltimatePos> {
+        Ok(UltimatePos::new(XOPos::index(board)?, XOPos::index(cell)?))
+    }
+
+    #[test]
+    fn win_board_and_free_choice() -> XOResult {
+        let mut game = UltimateXO::new();
+        // O take the middle row of board 0 while X is sent back to it
+        for &(board, cell) in [(0, 0), (0, 3), (3, 0), (0, 4), (4, 0), (0, 5)].iter() {
+            game.play(pos(board, cell)?)?;
+        }
+        assert_eq!(
+            game.board(XOPos::index(0)?).win_state(),
+            Some(XOTokenWinState::O)
+        );
+        assert_eq!(
+            game.meta_board().token_at(XOPos::index(0)?),
+            Some(XOToken::O)
+        );
+        assert_eq!(game.next_board(), Some(XOPos::index(5)?));
+        assert_eq!(
+            game.play(pos(4, 1)?),
+            Err(XOUltimateError::WrongBoardError {
+                board: 4,
+                expected: 5
+            })
+        );
+
+        // sent to a finished board means free choice, but never into a finished board
+        game.play(pos(5, 0)?)?;
+        assert_eq!(game.next_board(), None);
+        assert_eq!(
+            game.play(pos(0, 8)?),
+            Err(XOUltimateError::BoardFinishedError { board: 0 })
+        );
+        assert_eq!(game.legal_moves().len(), 8 * 9 - 3);
+        Ok(())
+    }
+
+    #[test]
+    fn playouts_end_consistently() -> XOResult {
+        let mut seed = 0x2545_f491_u32;
+        for _ in 0..200 {
+            let mut game = UltimateXO::new();
+            while game.win_state().is_none() {
+                // xorshift
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let moves = game.legal_moves();
+                assert!(!moves.is_empty());
+                game.play(moves[seed as usize % moves.len()])?;
+            }
+
+            let meta = game.meta_board();
+            match game.win_state() {
+                Some(XOTokenWinState::Stale) => {
+                    // the meta board may fill up without a line when every board was won
+                    assert!(matches!(
+                        meta.evaluate_winner(),
+                        None | Some(XOTokenWinState::Stale)
+                    ));
+                    assert!((0..9).all(|index| game.boards[index].game_ended()));
+                }
+                winner => assert_eq!(meta.evaluate_winner(), winner),
+            }
+            assert!(game.legal_moves().is_empty());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use crate::board::{XOBoard, RULES_SHIFT, WIN_STATE_SHIFT};
+use crate::table;
+use crate::token::XOToken;
+
+use custom_error::custom_error;
+use std::convert::TryFrom;
+
+custom_error! {
+/// Error use when a board's bits doesn't describe a position of legal play
+///
+/// Returned by [`XOBoard::validate`] and [`XOBoard::try_from`]
+///
+/// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+/// [`XOBoard::try_from`]: struct.XOBoard.html#impl-TryFrom%3Cu32%3E
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOBoardError
+    /// Error of having unused bits (bit 23 to 31) set
+    GarbageBitsError{bits: u32} = "unused bits must be zero, found {bits}",
+    /// Error of having X and O at the same cell
+    OverlapError{index: u32} = "both X and O are at position index {index}",
+    /// Error of having win state bits that isn't one of the 4 encodings
+    InvalidWinStateError{bits: u32} = "invalid win state bits {bits}",
+    /// Error of having number of X and O that can't happen with this turn's player
+    PieceCountError{x_count: u32, o_count: u32, turn: XOToken} =
+        "{x_count} X and {o_count} O can't happen with {turn}'s turn",
+    /// Error of both X and O completing a line
+    BothWinError = "both X and O have completed a line",
+    /// Error of having win state bits that disagree with [`XOBoard::evaluate_winner`]
+    WinStateMismatchError = "win state bits disagree with the board's cells",
+    /// Error of having a position that can't be reached by legal play
+    UnreachableError = "position can't be reached by legal play",
+}
+pub type XOBoardResult<T = ()> = Result<T, XOBoardError>;
+
+const USED_BITS: u32 = (0b1 << (RULES_SHIFT + 1)) - 1;
+
+impl XOBoard {
+    /// Check that the board can be reached by legal play from an empty board,
+    /// with either X or O starting
+    ///
+    /// # Error
+    /// Return the first problem found, checked in the order of [`XOBoardError`]'s variants
+    ///
+    /// ```rust
+    /// use bitboard_xo::{XOBoard, XOBoardError, XOToken};
+    ///
+    /// assert_eq!(XOBoard::empty().validate(), Ok(()));
+    /// assert_eq!(
+    ///     XOBoard::new(0b1 << 31).validate(),
+    ///     Err(XOBoardError::GarbageBitsError { bits: 0b1 << 31 })
+    /// );
+    /// assert_eq!(
+    ///     XOBoard::new(0b11).validate(),
+    ///     Err(XOBoardError::PieceCountError { x_count: 2, o_count: 0, turn: XOToken::X })
+    /// );
+    /// ```
+    ///
+    /// [`XOBoardError`]: enum.XOBoardError.html
+    pub fn validate(self) -> XOBoardResult {
+        let garbage = self.bits() & !USED_BITS;
+        if garbage != 0 {
+            return Err(XOBoardError::GarbageBitsError { bits: garbage });
+        }
+
+        let overlap = self.x_bit() & self.o_bit();
+        if overlap != 0 {
+            return Err(XOBoardError::OverlapError {
+                index: overlap.trailing_zeros(),
+            });
+        }
+
+        let win_state_bits = self.bits() >> WIN_STATE_SHIFT & 0b111;
+        if ![0b000, 0b011, 0b101, 0b111].contains(&win_state_bits) {
+            return Err(XOBoardError::InvalidWinStateError {
+                bits: win_state_bits,
+            });
+        }
+
+        let turn = self.turn();
+        let x_count = self.x_bit().count_ones();
+        let o_count = self.o_bit().count_ones();
+        let (turn_count, other_count) = match turn {
+            XOToken::X => (x_count, o_count),
+            XOToken::O => (o_count, x_count),
+        };
+        if turn_count > other_count || other_count > turn_count + 1 {
+            return Err(XOBoardError::PieceCountError {
+                x_count,
+                o_count,
+                turn,
+            });
+        }
+
+        if self.lines_of(XOToken::X).len() > 0 && self.lines_of(XOToken::O).len() > 0 {
+            return Err(XOBoardError::BothWinError);
+        }
+
+        if self.win_state() != self.evaluate_winner() {
+            return Err(XOBoardError::WinStateMismatchError);
+        }
+
+        // the player with more pieces (or the player to move if even) started,
+        // swap colors so X started
+        let starter = if turn_count == other_count {
+            turn
+        } else {
+            turn.opposite_token()
+        };
+        let x_bit = self.token_bit(starter);
+        let o_bit = self.token_bit(starter.opposite_token());
+        if !table::reachable_cells(x_bit, o_bit) {
+            return Err(XOBoardError::UnreachableError);
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<u32> for XOBoard {
+    type Error = XOBoardError;
+
+    /// Construct board from bits like [`XOBoard::new`], but [`XOBoard::validate`] it first
+    ///
+    /// [`XOBoard::new`]: struct.XOBoard.html#method.new
+    /// [`XOBoard::validate`]: struct.XOBoard.html#method.validate
+    fn try_from(bits: u32) -> XOBoardResult<XOBoard> {
+        let board = XOBoard::new(bits);
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::XORules;
+    use crate::token::XOTokenWinState;
+    use crate::xo_pos::XOPos;
+    use crate::XOResult;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_played_board_is_valid() {
+        let mut seen = HashSet::new();
+        let mut stack = vec![XOBoard::empty(), XOBoard::empty().swap_turn()];
+        stack.extend(
+            stack
+                .clone()
+                .iter()
+                .map(|board| board.set_rules(XORules::Misere)),
+        );
+        while let Some(board) = stack.pop() {
+            if seen.insert(board) {
+                assert_eq!(board.validate(), Ok(()), "{}", board);
+                stack.extend(board.children());
+            }
+        }
+
+        let valid = (0..=USED_BITS)
+            .filter(|&bits| XOBoard::try_from(bits).is_ok())
+            .count();
+        assert_eq!(valid, seen.len());
+    }
+
+    #[test]
+    fn detailed_error() -> XOResult {
+        use XOToken::*;
+
+        let board = XOBoard::from_maybe_token_array([
+            Some(X),
+            Some(X),
+            Some(X),
+            Some(O),
+            Some(O),
+            Some(O),
+            None,
+            None,
+            None,
+        ]);
+        assert_eq!(board.validate(), Err(XOBoardError::BothWinError));
+
+        let board = XOBoard::new(0b010 << WIN_STATE_SHIFT);
+        assert_eq!(
+            board.validate(),
+            Err(XOBoardError::InvalidWinStateError { bits: 0b010 })
+        );
+
+        let board = XOBoard::empty()
+            .play(XOPos::index(0)?)?
+            .play(XOPos::index(3)?)?
+            .play(XOPos::index(1)?)?
+            .play(XOPos::index(4)?)?
+            .play(XOPos::index(2)?)?;
+        assert_eq!(board.validate(), Ok(()));
+        let without_win_state = XOBoard::new(board.bits() & !(0b111 << WIN_STATE_SHIFT));
+        assert_eq!(
+            without_win_state.validate(),
+            Err(XOBoardError::WinStateMismatchError)
+        );
+
+        // X won, then O kept playing
+        let board = board.set(O, XOPos::index(8)?).swap_turn();
+        assert_eq!(board.win_state(), Some(XOTokenWinState::X));
+        assert_eq!(board.validate(), Err(XOBoardError::UnreachableError));
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+//! Wild xo: the player to move may place either X or O, and whoever complete a line win
+//!
+//! Since tokens no longer belong to a player, [`WildBoard`] keep the [`Player`] to move
+//! separately from the cells, and moves are [`WildMove`] carrying the token placed.
+//!
+//! ```rust
+//! # use bitboard_xo::XOResult;
+//! # fn main() -> XOResult {
+//! use bitboard_xo::wild::{WildBoard, WildMove};
+//! use bitboard_xo::{Player, XOPos, XOToken, XOValue};
+//!
+//! let board = WildBoard::new()
+//!     .play(WildMove::new(XOToken::O, XOPos::index(0)?))?
+//!     .play(WildMove::new(XOToken::O, XOPos::index(1)?))?;
+//! assert_eq!(board.turn(), Player::First);
+//! assert_eq!(board.legal_moves().len(), 2 * 7);
+//!
+//! // the first player complete the O line and win
+//! let board = board.play(WildMove::new(XOToken::O, XOPos::index(2)?))?;
+//! assert_eq!(board.winner(), Some(Player::First));
+//!
+//! // and the first player win from the start by playing at the center
+//! let solution = WildBoard::new().solve()?;
+//! assert_eq!(solution.value(), XOValue::Win);
+//! assert!(solution.best_moves().contains(&WildMove::new(XOToken::X, XOPos::index(4)?)));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`WildBoard`]: struct.WildBoard.html
+//! [`WildMove`]: struct.WildMove.html
+//! [`Player`]: ../enum.Player.html
+
+use crate::board::{XOBoard, XOGameError, XOGameResult, BIT_SHIFT};
+use crate::solver::{self, SearchPosition, XOSolveError, XOSolveResult, XOValue};
+use crate::table;
+use crate::token::{Player, XOToken};
+use crate::xo_pos::XOPos;
+
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Move of wild xo, placing `token` at `pos`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WildMove {
+    token: XOToken,
+    pos: XOPos,
+}
+
+impl WildMove {
+    /// Create move placing `token` at `pos`
+    pub fn new(token: XOToken, pos: XOPos) -> Self {
+        WildMove { token, pos }
+    }
+
+    /// Token being placed
+    pub fn token(self) -> XOToken {
+        self.token
+    }
+
+    /// Position the token is placed at
+    pub fn pos(self) -> XOPos {
+        self.pos
+    }
+
+    /// Bit of this move in a move mask, using the cell layout of [`XOBoard::new`]
+    ///
+    /// [`XOBoard::new`]: ../struct.XOBoard.html#method.new
+    fn bit(self) -> u32 {
+        match self.token {
+            XOToken::X => 0b1 << self.pos.as_index(),
+            XOToken::O => 0b1 << (self.pos.as_index() + BIT_SHIFT),
+        }
+    }
+}
+
+/// Board of wild xo, see [module documentation]
+///
+/// The cells are stored as [`XOBoard`], whose turn and win state bits are unused.
+///
+/// [module documentation]: index.html
+/// [`XOBoard`]: ../struct.XOBoard.html
+#[must_use = "WildBoard's method doesn't mutate but return a new board"]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WildBoard {
+    cells: XOBoard,
+    turn: Player,
+    winner: Option<Player>,
+}
+
+impl WildBoard {
+    /// Construct a new game with empty board and [`Player::First`] to move
+    ///
+    /// [`Player::First`]: ../enum.Player.html#variant.First
+    pub fn new() -> Self {
+        WildBoard {
+            cells: XOBoard::empty(),
+            turn: Player::First,
+            winner: None,
+        }
+    }
+
+    /// Retrieve the cells, only X and O bits of the board are set
+    pub fn board(self) -> XOBoard {
+        self.cells
+    }
+
+    /// Player who going to play
+    pub fn turn(self) -> Player {
+        self.turn
+    }
+
+    /// Player who completed a line, if any
+    pub fn winner(self) -> Option<Player> {
+        self.winner
+    }
+
+    /// Whether a line was completed or the board is full
+    pub fn game_ended(self) -> bool {
+        self.winner.is_some() || self.cells.free_bit() == 0
+    }
+
+    /// Play the game! This turn's player will place the move's token at the move's position
+    ///
+    /// # Error
+    /// Same as [`XOBoard::play`]
+    ///
+    /// [`XOBoard::play`]: ../struct.XOBoard.html#method.play
+    pub fn play(self, mv: WildMove) -> XOGameResult<WildBoard> {
+        if self.game_ended() {
+            return Err(XOGameError::GameEndedError);
+        }
+        if !self.cells.check_free_position(mv.pos) {
+            return Err(XOGameError::AlreadyPlayedError {
+                index: mv.pos.as_index(),
+            });
+        }
+        Ok(self.play_unchecked(mv))
+    }
+
+    fn play_unchecked(self, mv: WildMove) -> WildBoard {
+        let cells = self.cells.set(mv.token, mv.pos);
+        // the game was ongoing, so only a line of the token just placed can appear
+        let winner = if table::has_line_lookup(cells.token_bit(mv.token)) {
+            Some(self.turn)
+        } else {
+            None
+        };
+        WildBoard {
+            cells,
+            turn: self.turn.opposite(),
+            winner,
+        }
+    }
+
+    /// Bit mask of legal moves, bit 0-8 placing X and bit 9-17 placing O at each cell index
+    pub fn legal_move_bit(self) -> u32 {
+        if self.game_ended() {
+            0
+        } else {
+            let free = self.cells.free_bit();
+            free | free << BIT_SHIFT
+        }
+    }
+
+    /// Iterate through every legal move, X moves in index order then O moves in index order
+    pub fn legal_moves(self) -> WildMoveIter {
+        WildMoveIter {
+            bits: self.legal_move_bit(),
+        }
+    }
+
+    /// Iterate through every board reachable by one legal move,
+    /// in the same order as [`WildBoard::legal_moves`]
+    ///
+    /// [`WildBoard::legal_moves`]: struct.WildBoard.html#method.legal_moves
+    pub fn children(self) -> WildChildrenIter {
+        WildChildrenIter {
+            board: self,
+            moves: self.legal_moves(),
+        }
+    }
+
+    /// Solve the board with negamax (alpha-beta) search, like [`XOBoard::solve`]
+    ///
+    /// # Error
+    /// May return Err variant [`XOSolveError::GameEndedError`] if the game has already ended
+    ///
+    /// [`XOBoard::solve`]: ../struct.XOBoard.html#method.solve
+    /// [`XOSolveError::GameEndedError`]: ../enum.XOSolveError.html#variant.GameEndedError
+    pub fn solve(self) -> XOSolveResult<WildSolution> {
+        if self.game_ended() {
+            return Err(XOSolveError::GameEndedError);
+        }
+
+        let moves = self
+            .legal_moves()
+            .map(|mv| (mv.bit(), self.play_unchecked(mv)));
+        let (value, best_move_bit) = solver::solve_children(moves);
+        Ok(WildSolution {
+            value,
+            best_move_bit,
+        })
+    }
+}
+
+impl SearchPosition for WildBoard {
+    type Children = WildChildrenIter;
+
+    fn terminal_score(self) -> Option<i8> {
+        if self.winner.is_some() {
+            // whoever completed a line just moved
+            Some(-1)
+        } else if self.game_ended() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn children(self) -> WildChildrenIter {
+        WildBoard::children(self)
+    }
+}
+
+impl Default for WildBoard {
+    fn default() -> Self {
+        WildBoard::new()
+    }
+}
+
+impl Display for WildBoard {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self.winner {
+            Some(winner) => writeln!(f, "Game Ended: {}'s winner", winner)?,
+            None if self.game_ended() => writeln!(f, "Game Ended In Stale Mate")?,
+            None => writeln!(f, "{}'s Turn", self.turn)?,
+        }
+
+        for (index, token) in self.cells.iter().enumerate() {
+            match token {
+                Some(XOToken::X) => f.write_char('X')?,
+                Some(XOToken::O) => f.write_char('O')?,
+                None => f.write_char('.')?,
+            }
+            if index % 3 == 2 {
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of solving a board with [`WildBoard::solve`]
+///
+/// [`WildBoard::solve`]: struct.WildBoard.html#method.solve
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct WildSolution {
+    value: XOValue,
+    best_move_bit: u32,
+}
+
+impl WildSolution {
+    /// Game-theoretic value for the player whose turn it is
+    pub fn value(self) -> XOValue {
+        self.value
+    }
+
+    /// Optimal moves as a bit mask, using the layout of [`WildBoard::legal_move_bit`]
+    ///
+    /// [`WildBoard::legal_move_bit`]: struct.WildBoard.html#method.legal_move_bit
+    pub fn best_move_bit(self) -> u32 {
+        self.best_move_bit
+    }
+
+    /// Optimal moves in the order of [`WildBoard::legal_moves`]
+    ///
+    /// [`WildBoard::legal_moves`]: struct.WildBoard.html#method.legal_moves
+    pub fn best_moves(self) -> Vec<WildMove> {
+        WildMoveIter {
+            bits: self.best_move_bit,
+        }
+        .collect()
+    }
+}
+
+/// Iterator through moves of a move mask, see [`WildBoard::legal_moves`]
+///
+/// [`WildBoard::legal_moves`]: struct.WildBoard.html#method.legal_moves
+#[derive(Copy, Clone, Debug)]
+pub struct WildMoveIter {
+    bits: u32,
+}
+
+impl Iterator for WildMoveIter {
+    type Item = WildMove;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let index = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        let (token, index) = if index < BIT_SHIFT {
+            (XOToken::X, index)
+        } else {
+            (XOToken::O, index - BIT_SHIFT)
+        };
+        Some(WildMove::new(token, XOPos::new_unchecked(index)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for WildMoveIter {}
+
+/// Iterator through boards reachable from [`WildBoard`] by one legal move,
+/// constructed by [`WildBoard::children`]
+///
+/// [`WildBoard`]: struct.WildBoard.html
+/// [`WildBoard::children`]: struct.WildBoard.html#method.children
+#[derive(Copy, Clone, Debug)]
+pub struct WildChildrenIter {
+    board: WildBoard,
+    moves: WildMoveIter,
+}
+
+impl Iterator for WildChildrenIter {
+    type Item = WildBoard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board = self.board;
+        self.moves.next().map(|mv| board.play_unchecked(mv))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.moves.size_hint()
+    }
+}
+
+impl ExactSizeIterator for WildChildrenIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XOResult;
+
+    #[test]
+    fn any_line_win() -> XOResult {
+        let mut board = WildBoard::new();
+        for &(token, index) in [(XOToken::X, 0), (XOToken::O, 4), (XOToken::O, 8)].iter() {
+            board = board.play(WildMove::new(token, XOPos::index(index)?))?;
+        }
+        assert_eq!(board.winner(), None);
+        assert_eq!(
+            board.play(WildMove::new(XOToken::X, XOPos::index(4)?)),
+            Err(XOGameError::AlreadyPlayedError { index: 4 })
+        );
+
+        // the second player set up the X line, which the first player complete
+        board = board
+            .play(WildMove::new(XOToken::X, XOPos::index(1)?))?
+            .play(WildMove::new(XOToken::X, XOPos::index(2)?))?;
+        assert_eq!(board.winner(), Some(Player::First));
+        assert!(board.game_ended());
+        assert_eq!(board.legal_moves().count(), 0);
+        assert_eq!(
+            board.play(WildMove::new(XOToken::O, XOPos::index(3)?)),
+            Err(XOGameError::GameEndedError)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn moves_and_children_agree() -> XOResult {
+        let board = WildBoard::new().play(WildMove::new(XOToken::O, XOPos::index(4)?))?;
+        assert_eq!(board.legal_moves().len(), 16);
+        assert!(board
+            .legal_moves()
+            .zip(board.children())
+            .all(|(mv, child)| board.play(mv) == Ok(child)));
+        Ok(())
+    }
+
+    #[test]
+    fn best_moves_keep_value() -> XOResult {
+        let mut board = WildBoard::new();
+        for &(token, index) in [(XOToken::X, 0), (XOToken::O, 4), (XOToken::X, 8)].iter() {
+            board = board.play(WildMove::new(token, XOPos::index(index)?))?;
+        }
+        let solution = board.solve()?;
+        assert!(!solution.best_moves().is_empty());
+        for mv in solution.best_moves() {
+            let child = board.play(mv)?;
+            let value = if child.winner().is_some() {
+                XOValue::Win
+            } else if child.game_ended() {
+                XOValue::Draw
+            } else {
+                child.solve()?.value().opposite()
+            };
+            assert_eq!(value, solution.value());
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use custom_error::custom_error;
 use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
 custom_error! {
 /// Error use when attempting to constructing invalid [`XOPos`]
 ///
 /// [`XOPos`]: struct.XOPos.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub XOPosError
     InvalidXOIndex {index: u32} =
         "invalid xo's index: {index}, expecting integer in [0, 8] (correspond to xo's cell,
@@ -18,8 +22,18 @@ pub XOPosError
         "invalid xo's column: {col}, expecting one of 0, 1, 2 (correspond to left, middle, and right column)",
 }
 
+custom_error! {
+/// Error use when parsing a string that isn't a coordinate into [`XOPos`]
+///
+/// [`XOPos`]: struct.XOPos.html
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub XOCoordinateError {coordinate: String} =
+    "invalid xo's coordinate: {coordinate}, expecting column a, b, c followed by row 1, 2, 3 (such as b2 for the center)"
+}
+
 /// Position in xo-grid
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct XOPos {
     index: u32,
 }
@@ -50,6 +64,12 @@ impl XOPos {
         Ok(XOPos { index })
     }
 
+    /// Create [`XOPos`] from index already known to be in [0, 8]
+    pub(crate) fn new_unchecked(index: u32) -> Self {
+        debug_assert!(index < 9);
+        XOPos { index }
+    }
+
     /// Create [`XOPos`] from (row, column) position.
     ///
     /// (row, column) grid:
@@ -121,6 +141,55 @@ impl XOPos {
         debug_assert!(self.index < 9);
         self.index
     }
+
+    /// Retrieve row of this [`XOPos`] (0 for top row), guaranteed to be one of 0,1,2
+    ///
+    /// [`XOPos`]: struct.XOPos.html
+    pub fn row(self) -> u32 {
+        self.as_index() / 3
+    }
+
+    /// Retrieve column of this [`XOPos`] (0 for left column), guaranteed to be one of 0,1,2
+    ///
+    /// [`XOPos`]: struct.XOPos.html
+    pub fn col(self) -> u32 {
+        self.as_index() % 3
+    }
+}
+
+/// Format as coordinate, column `a`, `b`, `c` (left to right)
+/// followed by row `1`, `2`, `3` (top to bottom)
+///
+/// Coordinate grid:
+///
+/// ```text
+/// | a1 | b1 | c1 |
+/// | a2 | b2 | c2 |
+/// | a3 | b3 | c3 |
+/// ```
+impl Display for XOPos {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        let col = (b'a' + self.col() as u8) as char;
+        write!(f, "{}{}", col, self.row() + 1)
+    }
+}
+
+impl FromStr for XOPos {
+    type Err = XOCoordinateError;
+
+    /// Parse coordinate formatted by [`XOPos`]'s `Display`, such as `b2` for the center
+    ///
+    /// [`XOPos`]: struct.XOPos.html
+    fn from_str(s: &str) -> Result<Self, XOCoordinateError> {
+        match *s.as_bytes() {
+            [col @ b'a'..=b'c', row @ b'1'..=b'3'] => Ok(XOPos::new_unchecked(
+                u32::from(row - b'1') * 3 + u32::from(col - b'a'),
+            )),
+            _ => Err(XOCoordinateError {
+                coordinate: s.to_string(),
+            }),
+        }
+    }
 }
 
 impl TryFrom<u32> for XOPos {